@@ -0,0 +1,43 @@
+//! Reads arbitrary input as ADI, run with `cargo fuzz run reader` from the `adif` directory
+
+#![no_main]
+
+use adif::{reader::Reader, Band, Location, Mode, Record};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, serde::Deserialize)]
+#[allow(dead_code)]
+struct Contact {
+    call: String,
+    freq: Option<f64>,
+    qso_date: Option<String>,
+    qso_random: Option<bool>,
+    stx: Option<u32>,
+    band: Option<Band>,
+    mode: Option<Mode>,
+    lat: Option<Location>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(reader) = Reader::from_str(input) else {
+        return;
+    };
+    let _ = reader.header();
+
+    // strict mode must stop, not return the same error forever
+    for (i, record) in reader.deserialize::<Record>().unwrap().enumerate() {
+        assert!(i <= input.len(), "more records than bytes of input");
+        if let Ok(record) = record {
+            let _ = (record.get_date("QSO_DATE"), record.get_freq("FREQ"));
+        }
+    }
+
+    let mut contacts = reader.deserialize::<Contact>().unwrap().lenient();
+    contacts.by_ref().for_each(drop);
+    for diagnostic in contacts.diagnostics() {
+        let _ = diagnostic.to_string();
+    }
+});
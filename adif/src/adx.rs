@@ -0,0 +1,10 @@
+//! ADX, the XML form of ADIF, read and written through the same serde types as ADI
+
+mod reader;
+mod writer;
+
+#[cfg(test)]
+mod test;
+
+pub use reader::{DeserializeRecordsIter, Reader};
+pub use writer::Writer;
@@ -0,0 +1,411 @@
+use quick_xml::events::{BytesStart, Event};
+
+use crate::{
+    data_type::DataType,
+    header::Header,
+    reader::{deserialize_record, Cursor, Diagnostic, Error, Position, RecordError},
+};
+
+type XmlReader<'de> = quick_xml::Reader<&'de [u8]>;
+
+/// Reads ADX, each record is converted to ADI and deserialized the same way as
+/// [`crate::reader::Reader`] does
+pub struct Reader<'de> {
+    input: &'de str,
+    xml: XmlReader<'de>,
+    /// The header converted to ADI
+    header: Option<String>,
+    has_records: bool,
+}
+
+impl<'de> Reader<'de> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &'de str) -> Result<Self, Error> {
+        let mut xml = XmlReader::from_str(input);
+        let mut header = None;
+
+        loop {
+            match xml.read_event()? {
+                Event::Start(e) if e.local_name().as_ref().eq_ignore_ascii_case(b"adx") => break,
+                Event::Decl(_) | Event::Comment(_) | Event::DocType(_) | Event::PI(_) => {}
+                Event::Text(t) if is_whitespace(&t) => {}
+                Event::Eof => return Err(Error::NoData),
+                _ => return Err(Error::OtherError("expected an `ADX` element")),
+            }
+        }
+
+        let has_records = loop {
+            match xml.read_event()? {
+                Event::Start(e) if is_element(&e, b"header") && header.is_none() => {
+                    let text = read_header(&mut xml)?;
+                    Header::parse(&text)?;
+                    header = Some(text);
+                }
+                Event::Start(e) if is_element(&e, b"records") => break true,
+                Event::Empty(e) if is_element(&e, b"records") || is_element(&e, b"header") => {}
+                Event::End(_) | Event::Eof => break false,
+                Event::Comment(_) | Event::PI(_) => {}
+                Event::Text(t) if is_whitespace(&t) => {}
+                _ => {
+                    return Err(Error::OtherError(
+                        "expected a `HEADER` or `RECORDS` element",
+                    ))
+                }
+            }
+        };
+
+        Ok(Self {
+            input,
+            xml,
+            header,
+            has_records,
+        })
+    }
+
+    pub fn header(&self) -> Option<Header<'_>> {
+        self.header.as_deref().map(|h| {
+            Header::parse(h)
+                .expect("header was validated when it was read")
+                .0
+        })
+    }
+
+    /// The header converted to ADI, including the `<EOH>`
+    pub(crate) fn header_adi(&self) -> Option<&str> {
+        self.header.as_deref()
+    }
+
+    /// Records are converted to ADI text first, so they cannot borrow from the input
+    pub fn deserialize<D: serde::de::DeserializeOwned>(self) -> DeserializeRecordsIter<'de, D> {
+        DeserializeRecordsIter {
+            input: self.input,
+            xml: self.xml,
+            cursor: Cursor::default(),
+            record_name: Vec::new(),
+            record: 0,
+            lenient: false,
+            diagnostics: Vec::new(),
+            done: !self.has_records,
+            _priv: std::marker::PhantomData,
+        }
+    }
+}
+
+impl core::fmt::Debug for Reader<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reader")
+            .field("header", &self.header)
+            .field("has_records", &self.has_records)
+            .finish_non_exhaustive()
+    }
+}
+
+fn is_element(e: &BytesStart, name: &[u8]) -> bool {
+    e.local_name().as_ref().eq_ignore_ascii_case(name)
+}
+
+fn is_whitespace(text: &[u8]) -> bool {
+    text.iter().all(u8::is_ascii_whitespace)
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Result<Option<String>, Error> {
+    for attr in e.attributes() {
+        let attr = attr.map_err(quick_xml::Error::from)?;
+        if attr
+            .key
+            .local_name()
+            .as_ref()
+            .eq_ignore_ascii_case(name.as_bytes())
+        {
+            return Ok(Some(attr.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn type_attribute(e: &BytesStart) -> Result<Option<char>, Error> {
+    let Some(typ) = attribute(e, "TYPE")? else {
+        return Ok(None);
+    };
+
+    let mut chars = typ.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => DataType::from_indicator(c)
+            .map(|_| Some(c))
+            .ok_or(Error::InvalidDataType(c)),
+        _ => Err(Error::OtherError(
+            "`TYPE` must be a single data type indicator",
+        )),
+    }
+}
+
+fn element_name(e: &BytesStart) -> Result<String, Error> {
+    let name = e.local_name();
+    let name = std::str::from_utf8(name.as_ref())
+        .map_err(|_| Error::OtherError("element name is not valid utf-8"))?;
+    Ok(name.to_owned())
+}
+
+/// Reads the text of a field element up to its end tag
+fn read_text(xml: &mut XmlReader) -> Result<String, Error> {
+    let mut value = String::new();
+    loop {
+        match xml.read_event()? {
+            Event::Text(t) => value.push_str(&t.unescape()?),
+            Event::CData(c) => value.push_str(
+                std::str::from_utf8(&c.into_inner())
+                    .map_err(|_| Error::OtherError("CDATA is not valid utf-8"))?,
+            ),
+            Event::End(_) => return Ok(value),
+            Event::Comment(_) | Event::PI(_) => {}
+            Event::Eof => return Err(Error::UnexpectedEndOfInput("while reading ADX field")),
+            _ => return Err(Error::OtherError("ADX fields cannot contain elements")),
+        }
+    }
+}
+
+/// Appends a field in ADI form, `name` must not contain any of `:<>`
+fn push_field(adi: &mut String, name: &str, data_type: Option<char>, value: &str) {
+    adi.push('<');
+    adi.push_str(name);
+    adi.push(':');
+    adi.push_str(&value.len().to_string());
+    if let Some(t) = data_type {
+        adi.push(':');
+        adi.push(t);
+    }
+    adi.push('>');
+    adi.push_str(value);
+    adi.push(' ');
+}
+
+fn check_name(name: &str) -> Result<(), Error> {
+    if name.is_empty() || name.contains([':', '<', '>', ',', '{', '}']) {
+        return Err(Error::OtherError("invalid ADX field name"));
+    }
+    Ok(())
+}
+
+/// Reads the contents of `<HEADER>` and converts it to an ADI header
+fn read_header(xml: &mut XmlReader) -> Result<String, Error> {
+    let mut adi = String::new();
+    loop {
+        let (e, empty) = match xml.read_event()? {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::End(_) => {
+                adi.push_str("<EOH>");
+                return Ok(adi);
+            }
+            Event::Comment(_) | Event::PI(_) => continue,
+            Event::Text(t) if is_whitespace(&t) => continue,
+            Event::Eof => return Err(Error::UnexpectedEndOfInput("while reading ADX header")),
+            _ => return Err(Error::OtherError("unexpected content in ADX header")),
+        };
+        let value = if empty {
+            String::new()
+        } else {
+            read_text(xml)?
+        };
+
+        if is_element(&e, b"userdef") {
+            let Some(id) = attribute(&e, "FIELDID")? else {
+                return Err(Error::OtherError("`USERDEF` in header has no `FIELDID`"));
+            };
+            let id: u32 = id.trim().parse()?;
+
+            let limits = match attribute(&e, "ENUM")? {
+                Some(l) => Some(l),
+                None => attribute(&e, "RANGE")?,
+            };
+            let value = match limits {
+                Some(l) => format!(
+                    "{},{{{}}}",
+                    value.trim(),
+                    l.trim().trim_start_matches('{').trim_end_matches('}')
+                ),
+                None => value,
+            };
+            push_field(
+                &mut adi,
+                &format!("USERDEF{}", id),
+                type_attribute(&e)?,
+                &value,
+            );
+        } else {
+            let name = element_name(&e)?;
+            check_name(&name)?;
+            push_field(&mut adi, &name, None, &value);
+        }
+    }
+}
+
+/// Reads the contents of a `<RECORD>` and converts it to an ADI record
+fn read_record(xml: &mut XmlReader, field: &mut Option<String>) -> Result<String, Error> {
+    let mut adi = String::new();
+    loop {
+        let (e, empty) = match xml.read_event()? {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::End(_) => {
+                adi.push_str("<EOR>");
+                return Ok(adi);
+            }
+            Event::Comment(_) | Event::PI(_) => continue,
+            Event::Text(t) if is_whitespace(&t) => continue,
+            Event::Eof => return Err(Error::UnexpectedEndOfInput("while reading ADX record")),
+            _ => return Err(Error::OtherError("unexpected content in ADX record")),
+        };
+
+        *field = element_name(&e).ok();
+        let (name, data_type) = if is_element(&e, b"app") {
+            let (Some(program), Some(name)) =
+                (attribute(&e, "PROGRAMID")?, attribute(&e, "FIELDNAME")?)
+            else {
+                return Err(Error::OtherError(
+                    "`APP` needs `PROGRAMID` and `FIELDNAME` attributes",
+                ));
+            };
+            (format!("APP_{}_{}", program, name), type_attribute(&e)?)
+        } else if is_element(&e, b"userdef") {
+            let Some(name) = attribute(&e, "FIELDNAME")? else {
+                return Err(Error::OtherError("`USERDEF` in record has no `FIELDNAME`"));
+            };
+            (name, type_attribute(&e)?)
+        } else {
+            (element_name(&e)?, None)
+        };
+        *field = Some(name.clone());
+        check_name(&name)?;
+
+        let value = if empty {
+            String::new()
+        } else {
+            read_text(xml)?
+        };
+        push_field(&mut adi, &name, data_type, &value);
+    }
+}
+
+pub struct DeserializeRecordsIter<'de, D> {
+    input: &'de str,
+    xml: XmlReader<'de>,
+    /// Position of the last record that was started
+    cursor: Cursor,
+    /// Name of the last record element, as it appeared in the input
+    record_name: Vec<u8>,
+    record: usize,
+    lenient: bool,
+    diagnostics: Vec<Diagnostic>,
+    done: bool,
+    _priv: std::marker::PhantomData<D>,
+}
+
+impl<D> DeserializeRecordsIter<'_, D> {
+    /// Skips malformed records instead of returning errors, see [`Self::diagnostics`]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Errors for the records that were skipped in lenient mode
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn error_position(&self, offset: usize, field: Option<String>) -> Position {
+        let mut cursor = self.cursor;
+        let offset = offset.clamp(cursor.offset, self.input.len());
+        cursor.advance(&self.input.as_bytes()[cursor.offset..offset]);
+        cursor.position(self.record, field)
+    }
+
+    /// Moves to the start of the next `<RECORD>`, returns `false` if there are no more records
+    fn next_record(&mut self) -> Result<bool, Error> {
+        loop {
+            let start = self.xml.buffer_position();
+            match self.xml.read_event()? {
+                Event::Start(e) if is_element(&e, b"record") => {
+                    let start = start + self.input[start..].find('<').unwrap_or(0);
+                    self.cursor
+                        .advance(&self.input.as_bytes()[self.cursor.offset..start]);
+                    self.record_name = e.name().as_ref().to_vec();
+                    return Ok(true);
+                }
+                Event::Empty(e) if is_element(&e, b"record") => {}
+                Event::End(_) | Event::Eof => return Ok(false),
+                Event::Comment(_) | Event::PI(_) => {}
+                Event::Text(t) if is_whitespace(&t) => {}
+                _ => return Err(Error::OtherError("expected a `RECORD` element")),
+            }
+        }
+    }
+}
+
+impl<D> core::fmt::Debug for DeserializeRecordsIter<'_, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeserializeRecordsIter")
+            .field("record", &self.record)
+            .field("lenient", &self.lenient)
+            .field("diagnostics", &self.diagnostics)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<D: serde::de::DeserializeOwned> Iterator for DeserializeRecordsIter<'_, D> {
+    type Item = Result<D, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let (error, position) = match self.next_record() {
+                Ok(true) => {
+                    let mut field = None;
+                    match read_record(&mut self.xml, &mut field) {
+                        Ok(adi) => match deserialize_record(&mut adi.as_str()) {
+                            Ok(record) => {
+                                self.record += 1;
+                                return Some(Ok(record));
+                            }
+                            Err(RecordError { error, field, .. }) => {
+                                (error, self.error_position(0, field))
+                            }
+                        },
+                        Err(Error::Xml(e)) => {
+                            let position = self.error_position(self.xml.buffer_position(), field);
+                            (Error::Xml(e), position)
+                        }
+                        Err(error) => {
+                            let position = self.error_position(self.xml.buffer_position(), field);
+                            let name = quick_xml::name::QName(&self.record_name);
+                            if self.xml.read_to_end(name).is_err() {
+                                self.done = true;
+                            }
+                            (error, position)
+                        }
+                    }
+                }
+                Ok(false) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(error) => (error, self.error_position(self.xml.buffer_position(), None)),
+            };
+
+            self.record += 1;
+
+            if !self.lenient || matches!(error, Error::Xml(_)) {
+                // XML errors leave the reader somewhere unknown in the document
+                self.done = true;
+                return Some(Err(error.at(position)));
+            }
+
+            self.diagnostics.push(Diagnostic { position, error });
+        }
+        None
+    }
+}
@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Reader, Writer};
+use crate::{data_type::DataType, reader::Error, record::Record, writer};
+
+const INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ADX>
+  <HEADER>
+    <!-- exported by hand -->
+    <ADIF_VER>3.1.4</ADIF_VER>
+    <PROGRAMID>Test</PROGRAMID>
+    <USERDEF FIELDID="1" TYPE="N" RANGE="{5:20}">EPC</USERDEF>
+    <USERDEF FIELDID="2" TYPE="E" ENUM="{S,M,L}">SWEATERSIZE</USERDEF>
+  </HEADER>
+  <RECORDS>
+    <RECORD>
+      <CALL>W1AW</CALL>
+      <FREQ>14.074</FREQ>
+      <QSL_RCVD>Y</QSL_RCVD>
+      <COMMENT>Tom &amp; Jerry &lt;3</COMMENT>
+      <APP PROGRAMID="N1MM" FIELDNAME="ISRUNQSO" TYPE="B">Y</APP>
+      <USERDEF FIELDNAME="EPC">10</USERDEF>
+    </RECORD>
+    <RECORD>
+      <CALL>K9ET</CALL>
+      <NAME_INTL><![CDATA[Jürgen]]></NAME_INTL>
+      <COMMENT/>
+    </RECORD>
+  </RECORDS>
+</ADX>
+"#;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Contact {
+    call: String,
+    freq: Option<f64>,
+    qsl_rcvd: Option<bool>,
+    comment: Option<String>,
+    name_intl: Option<String>,
+    #[serde(rename = "app_n1mm_isrunqso")]
+    is_run: Option<bool>,
+    epc: Option<u32>,
+}
+
+fn read<D: serde::de::DeserializeOwned>(input: &str) -> Result<Vec<D>, Error> {
+    Reader::from_str(input)?
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(Error::into_inner)
+}
+
+#[test]
+fn header() {
+    let reader = Reader::from_str(INPUT).unwrap();
+    let header = reader.header().unwrap();
+    assert_eq!(header.adif_ver, Some("3.1.4"));
+    assert_eq!(header.program_id, Some("Test"));
+
+    let epc = header.user_defined_field("epc").unwrap();
+    assert_eq!(epc.id, 1);
+    assert_eq!(epc.data_type, Some(DataType::Number));
+    assert_eq!(epc.range, Some((5.0, 20.0)));
+
+    let size = header.user_defined_field("SWEATERSIZE").unwrap();
+    assert_eq!(size.enumeration, Some(vec!["S", "M", "L"]));
+}
+
+#[test]
+fn records() {
+    let contacts: Vec<Contact> = read(INPUT).unwrap();
+    assert_eq!(
+        contacts,
+        vec![
+            Contact {
+                call: "W1AW".to_owned(),
+                freq: Some(14.074),
+                qsl_rcvd: Some(true),
+                comment: Some("Tom & Jerry <3".to_owned()),
+                name_intl: None,
+                is_run: Some(true),
+                epc: Some(10),
+            },
+            Contact {
+                call: "K9ET".to_owned(),
+                freq: None,
+                qsl_rcvd: None,
+                comment: Some(String::new()),
+                name_intl: Some("Jürgen".to_owned()),
+                is_run: None,
+                epc: None,
+            }
+        ]
+    );
+
+    let records: Vec<Record> = read(INPUT).unwrap();
+    let app = records[0].field("app_n1mm_isrunqso").unwrap();
+    assert_eq!(app.name, "APP_N1MM_ISRUNQSO");
+    assert_eq!(app.data_type, Some(DataType::Boolean));
+}
+
+#[test]
+fn no_header() {
+    let input = "<ADX><RECORDS><RECORD><CALL>W1AW</CALL></RECORD></RECORDS></ADX>";
+    let reader = Reader::from_str(input).unwrap();
+    assert!(reader.header().is_none());
+    let records: Vec<Record> = reader.deserialize().collect::<Result<_, _>>().unwrap();
+    assert_eq!(records[0].get("call"), Some("W1AW"));
+
+    let records: Vec<Record> = read("<ADX><HEADER/></ADX>").unwrap();
+    assert!(records.is_empty());
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(read::<Record>(""), Err(Error::NoData)));
+    assert!(matches!(
+        read::<Record>("<ADIF></ADIF>"),
+        Err(Error::OtherError(_))
+    ));
+    assert!(matches!(
+        read::<Contact>("<ADX><RECORDS><RECORD><CALL>W1AW</CALL><FREQ>x</FREQ></RECORD>"),
+        Err(Error::ParseFloatError(_))
+    ));
+    assert!(matches!(
+        read::<Record>("<ADX><RECORDS><RECORD><CALL>W1AW</CALL>"),
+        Err(Error::Xml(_)) | Err(Error::UnexpectedEndOfInput(_))
+    ));
+    assert!(matches!(
+        read::<Record>("<ADX><RECORDS><RECORD><APP FIELDNAME=\"ID\">1</APP></RECORD>"),
+        Err(Error::OtherError(_))
+    ));
+}
+
+#[test]
+fn lenient() {
+    let input = "<ADX>\n<RECORDS>\n<RECORD><CALL>W1AW</CALL></RECORD>\n\
+        <RECORD><CALL>K9ET</CALL><APP TYPE=\"B\">Y</APP><FREQ>7</FREQ></RECORD>\n\
+        <RECORD><CALL>KD9M</CALL><FREQ>x</FREQ></RECORD>\n\
+        <RECORD><CALL>JA1ABC</CALL></RECORD>\n</RECORDS>\n</ADX>";
+
+    let mut records = Reader::from_str(input)
+        .unwrap()
+        .deserialize::<Contact>()
+        .lenient();
+    let calls: Vec<_> = (&mut records).map(|r| r.unwrap().call).collect();
+    assert_eq!(calls, vec!["W1AW", "JA1ABC"]);
+
+    let diagnostics = records.diagnostics();
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| (
+                d.position.record,
+                d.position.line,
+                d.position.field.as_deref()
+            ))
+            .collect::<Vec<_>>(),
+        vec![(1, 4, Some("APP")), (2, 5, Some("FREQ"))]
+    );
+}
+
+#[derive(Serialize)]
+struct Header<'s> {
+    adif_ver: &'s str,
+    userdef1: &'s str,
+}
+
+#[test]
+fn round_trip() {
+    let contact = Contact {
+        call: "W1AW".to_owned(),
+        freq: Some(14.074),
+        qsl_rcvd: Some(false),
+        comment: Some("<5 & \"quoted\"".to_owned()),
+        name_intl: Some("Łukasz".to_owned()),
+        is_run: Some(true),
+        epc: Some(12),
+    };
+
+    let mut writer = Writer::new(Vec::new());
+    writer
+        .write_header(&Header {
+            adif_ver: "3.1.4",
+            userdef1: "EPC,{5:20}",
+        })
+        .unwrap();
+    writer.serialize(&contact).unwrap();
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    assert!(output.contains("<USERDEF FIELDID=\"1\" TYPE=\"N\" RANGE=\"{5:20}\">EPC</USERDEF>"));
+    assert!(output.contains("<APP PROGRAMID=\"N1MM\" FIELDNAME=\"ISRUNQSO\">Y</APP>"));
+    assert!(output.contains("<USERDEF FIELDNAME=\"EPC\">12</USERDEF>"));
+
+    let reader = Reader::from_str(&output).unwrap();
+    assert_eq!(
+        reader
+            .header()
+            .unwrap()
+            .user_defined_field("EPC")
+            .unwrap()
+            .range,
+        Some((5.0, 20.0))
+    );
+    assert_eq!(read::<Contact>(&output).unwrap(), vec![contact]);
+}
+
+#[test]
+fn record_round_trip() {
+    let records: Vec<Record> = read(INPUT).unwrap();
+
+    let mut writer = Writer::new(Vec::new());
+    for record in &records {
+        writer.write_record(record).unwrap();
+    }
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert!(output.contains("<APP PROGRAMID=\"N1MM\" FIELDNAME=\"ISRUNQSO\" TYPE=\"B\">Y</APP>"));
+
+    // without a header the user defined field is written as a normal element
+    assert!(output.contains("<EPC>10</EPC>"));
+    assert_eq!(read::<Record>(&output).unwrap(), records);
+}
+
+#[test]
+fn writer_errors() {
+    let mut writer = Writer::new(Vec::new());
+    writer
+        .serialize(&Contact {
+            call: "W1AW".to_owned(),
+            freq: None,
+            qsl_rcvd: None,
+            comment: None,
+            name_intl: None,
+            is_run: None,
+            epc: None,
+        })
+        .unwrap();
+    assert!(matches!(
+        writer.write_header(&()),
+        Err(writer::Error::HeaderAfterRecords)
+    ));
+
+    let mut record = Record::new();
+    record.insert("1ST", None, "x");
+    assert!(matches!(
+        writer.write_record(&record),
+        Err(writer::Error::InvalidFieldName(_))
+    ));
+}
@@ -0,0 +1,203 @@
+use quick_xml::escape::escape;
+use serde::Serialize;
+
+use crate::{
+    record::Record,
+    writer::{check_field_name, serialize_fields, Error, FieldWriter},
+};
+
+/// Writes ADX, the header and records take the same serde types as [`crate::writer::Writer`]
+#[derive(Debug)]
+pub struct Writer<W> {
+    writer: W,
+    started: bool,
+    /// Names from the `USERDEFn` header fields, written as `USERDEF` elements in records
+    user_defined_fields: Vec<String>,
+}
+
+impl<W: std::io::Write> Writer<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            started: false,
+            user_defined_fields: Vec::new(),
+        }
+    }
+
+    /// Writes the header, a `USERDEFn` value of `name,{A,B}` is an enumeration and
+    /// `name,{min:max}` a numeric range
+    pub fn write_header<H: Serialize>(&mut self, header: &H) -> Result<(), Error> {
+        if self.started {
+            return Err(Error::HeaderAfterRecords);
+        }
+
+        self.writer
+            .write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ADX>\n<HEADER>\n")?;
+        serialize_fields(
+            header,
+            &mut HeaderFields {
+                writer: &mut self.writer,
+                user_defined_fields: &mut self.user_defined_fields,
+            },
+        )?;
+        self.writer.write_all(b"</HEADER>\n<RECORDS>\n")?;
+        self.started = true;
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), Error> {
+        if !self.started {
+            self.writer
+                .write_all(b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ADX>\n<RECORDS>\n")?;
+            self.started = true;
+        }
+        Ok(())
+    }
+
+    pub fn serialize<S: Serialize>(&mut self, record: &S) -> Result<(), Error> {
+        self.start()?;
+        self.writer.write_all(b"<RECORD>\n")?;
+        serialize_fields(record, &mut self.record_fields())?;
+        self.writer.write_all(b"</RECORD>\n")?;
+        Ok(())
+    }
+
+    /// Writes a [`Record`], type indicators are kept for `APP_` and user defined fields
+    pub fn write_record(&mut self, record: &Record) -> Result<(), Error> {
+        self.start()?;
+        self.writer.write_all(b"<RECORD>\n")?;
+        let mut fields = self.record_fields();
+        for field in record {
+            fields.write_field(
+                &field.name,
+                field.data_type.map(|t| t.indicator()),
+                &field.value,
+            )?;
+        }
+        self.writer.write_all(b"</RECORD>\n")?;
+        Ok(())
+    }
+
+    fn record_fields(&mut self) -> RecordFields<'_, W> {
+        RecordFields {
+            writer: &mut self.writer,
+            user_defined_fields: &self.user_defined_fields,
+        }
+    }
+
+    /// Closes the document and returns the inner writer
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.start()?;
+        self.writer.write_all(b"</RECORDS>\n</ADX>\n")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// ADI allows more characters in field names than XML does in element names
+fn check_element_name(name: &str) -> Result<(), Error> {
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(Error::InvalidFieldName(name.to_owned()));
+    }
+    Ok(())
+}
+
+struct HeaderFields<'w, W> {
+    writer: &'w mut W,
+    user_defined_fields: &'w mut Vec<String>,
+}
+
+impl<W: std::io::Write> FieldWriter for HeaderFields<'_, W> {
+    fn write_field(
+        &mut self,
+        name: &str,
+        data_type: Option<char>,
+        value: &str,
+    ) -> Result<(), Error> {
+        check_field_name(name)?;
+        let name = name.to_ascii_uppercase();
+
+        let Some(Ok(id)) = name.strip_prefix("USERDEF").map(str::parse::<u32>) else {
+            check_element_name(&name)?;
+            writeln!(self.writer, "<{0}>{1}</{0}>", name, escape(value))?;
+            return Ok(());
+        };
+
+        let (field_name, limits) = match value.split_once(',') {
+            Some((n, l)) => (n.trim(), Some(l.trim())),
+            None => (value.trim(), None),
+        };
+        let (data_type, limits) = match limits {
+            Some(l) if l.contains(':') => (
+                data_type.unwrap_or('N'),
+                format!(" RANGE=\"{}\"", escape(l)),
+            ),
+            Some(l) => (data_type.unwrap_or('E'), format!(" ENUM=\"{}\"", escape(l))),
+            None => (data_type.unwrap_or('S'), String::new()),
+        };
+
+        writeln!(
+            self.writer,
+            "<USERDEF FIELDID=\"{}\" TYPE=\"{}\"{}>{}</USERDEF>",
+            id,
+            data_type,
+            limits,
+            escape(field_name)
+        )?;
+        self.user_defined_fields.push(field_name.to_owned());
+        Ok(())
+    }
+}
+
+struct RecordFields<'w, W> {
+    writer: &'w mut W,
+    user_defined_fields: &'w [String],
+}
+
+impl<W: std::io::Write> FieldWriter for RecordFields<'_, W> {
+    fn write_field(
+        &mut self,
+        name: &str,
+        data_type: Option<char>,
+        value: &str,
+    ) -> Result<(), Error> {
+        check_field_name(name)?;
+        let type_attribute = data_type
+            .map(|t| format!(" TYPE=\"{}\"", t))
+            .unwrap_or_default();
+
+        if let Some(user_defined) = self
+            .user_defined_fields
+            .iter()
+            .find(|f| f.eq_ignore_ascii_case(name))
+        {
+            writeln!(
+                self.writer,
+                "<USERDEF FIELDNAME=\"{}\"{}>{}</USERDEF>",
+                escape(user_defined),
+                type_attribute,
+                escape(value)
+            )?;
+            return Ok(());
+        }
+
+        let name = name.to_ascii_uppercase();
+        match name.strip_prefix("APP_").and_then(|n| n.split_once('_')) {
+            Some((program, field)) if !program.is_empty() && !field.is_empty() => writeln!(
+                self.writer,
+                "<APP PROGRAMID=\"{}\" FIELDNAME=\"{}\"{}>{}</APP>",
+                escape(program),
+                escape(field),
+                type_attribute,
+                escape(value)
+            )?,
+            _ => {
+                check_element_name(&name)?;
+                writeln!(self.writer, "<{0}>{1}</{0}>", name, escape(value))?
+            }
+        }
+        Ok(())
+    }
+}
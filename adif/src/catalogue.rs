@@ -0,0 +1,238 @@
+use crate::{data_type::DataType, enumeration::Enumeration, location::is_grid_square};
+
+/// A QSO field defined by the ADIF specification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub data_type: DataType,
+    pub check: Option<Check>,
+}
+
+/// Extra checks on a value beyond its data type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check {
+    Enumeration(Enumeration),
+    /// Inclusive numeric range
+    Range(f64, f64),
+    PositiveInteger,
+    GridSquare,
+}
+
+impl Check {
+    pub fn is_valid(&self, value: &str) -> bool {
+        if value.is_empty() {
+            return true;
+        }
+
+        match self {
+            Self::Enumeration(e) => e.contains(value),
+            Self::Range(min, max) => value
+                .parse::<f64>()
+                .is_ok_and(|v| (*min..=*max).contains(&v)),
+            Self::PositiveInteger => value.parse::<u32>().is_ok_and(|v| v > 0),
+            Self::GridSquare => is_grid_square(value),
+        }
+    }
+}
+
+impl core::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enumeration(e) => write!(f, "a {} value", e),
+            Self::Range(min, max) => write!(f, "a number from {} to {}", min, max),
+            Self::PositiveInteger => f.write_str("a positive integer"),
+            Self::GridSquare => f.write_str("a Maidenhead grid square"),
+        }
+    }
+}
+
+/// Finds a field by name, ignoring case
+pub fn field(name: &str) -> Option<&'static FieldInfo> {
+    FIELDS.iter().find(|f| f.name.eq_ignore_ascii_case(name))
+}
+
+macro_rules! fields {
+    ($($name:literal $typ:ident $(: $check:ident $(($($arg:expr),*))?)?,)*) => {
+        &[$(FieldInfo {
+            name: $name,
+            data_type: DataType::$typ,
+            check: fields!(@check $($check $(($($arg),*))?)?),
+        },)*]
+    };
+    (@check) => { None };
+    (@check $check:ident $(($($arg:expr),*))?) => { Some(Check::$check $(($($arg),*))?) };
+}
+
+/// The QSO fields of ADIF 3.1.4
+pub static FIELDS: &[FieldInfo] = fields![
+    "ADDRESS" MultilineString,
+    "ADDRESS_INTL" IntlMultilineString,
+    "AGE" Number: Range(0.0, 120.0),
+    "ALTITUDE" Number,
+    "ANT_AZ" Number: Range(0.0, 360.0),
+    "ANT_EL" Number: Range(-90.0, 90.0),
+    "ANT_PATH" Enumeration: Enumeration(Enumeration::AntPath),
+    "ARRL_SECT" Enumeration: Enumeration(Enumeration::ArrlSection),
+    "AWARD_GRANTED" String,
+    "AWARD_SUBMITTED" String,
+    "A_INDEX" Number: Range(0.0, 400.0),
+    "BAND" Enumeration: Enumeration(Enumeration::Band),
+    "BAND_RX" Enumeration: Enumeration(Enumeration::Band),
+    "CALL" String,
+    "CHECK" String,
+    "CLASS" String,
+    "CLUBLOG_QSO_UPLOAD_DATE" Date,
+    "CLUBLOG_QSO_UPLOAD_STATUS" Enumeration: Enumeration(Enumeration::QsoUploadStatus),
+    "CNTY" Enumeration,
+    "COMMENT" String,
+    "COMMENT_INTL" IntlString,
+    "CONT" Enumeration: Enumeration(Enumeration::Continent),
+    "CONTACTED_OP" String,
+    "CONTEST_ID" String,
+    "COUNTRY" String,
+    "COUNTRY_INTL" IntlString,
+    "CQZ" Number: Range(1.0, 40.0),
+    "CREDIT_GRANTED" String,
+    "CREDIT_SUBMITTED" String,
+    "DARC_DOK" Enumeration,
+    "DCL_QSLRDATE" Date,
+    "DCL_QSLSDATE" Date,
+    "DCL_QSL_RCVD" Enumeration: Enumeration(Enumeration::QslRcvd),
+    "DCL_QSL_SENT" Enumeration: Enumeration(Enumeration::QslSent),
+    "DISTANCE" Number: Range(0.0, f64::MAX),
+    "DXCC" Enumeration: Range(0.0, 522.0),
+    "EMAIL" String,
+    "EQ_CALL" String,
+    "EQSL_QSLRDATE" Date,
+    "EQSL_QSLSDATE" Date,
+    "EQSL_QSL_RCVD" Enumeration: Enumeration(Enumeration::QslRcvd),
+    "EQSL_QSL_SENT" Enumeration: Enumeration(Enumeration::QslSent),
+    "FISTS" Number: PositiveInteger,
+    "FISTS_CC" Number: PositiveInteger,
+    "FORCE_INIT" Boolean,
+    "FREQ" Number,
+    "FREQ_RX" Number,
+    "GRIDSQUARE" String: GridSquare,
+    "GRIDSQUARE_EXT" String,
+    "GUEST_OP" String,
+    "HAMLOGEU_QSO_UPLOAD_DATE" Date,
+    "HAMLOGEU_QSO_UPLOAD_STATUS" Enumeration: Enumeration(Enumeration::QsoUploadStatus),
+    "HAMQTH_QSO_UPLOAD_DATE" Date,
+    "HAMQTH_QSO_UPLOAD_STATUS" Enumeration: Enumeration(Enumeration::QsoUploadStatus),
+    "HRDLOG_QSO_UPLOAD_DATE" Date,
+    "HRDLOG_QSO_UPLOAD_STATUS" Enumeration: Enumeration(Enumeration::QsoUploadStatus),
+    "IOTA" String,
+    "IOTA_ISLAND_ID" Number: PositiveInteger,
+    "ITUZ" Number: Range(1.0, 90.0),
+    "K_INDEX" Number: Range(0.0, 9.0),
+    "LAT" Location,
+    "LON" Location,
+    "LOTW_QSLRDATE" Date,
+    "LOTW_QSLSDATE" Date,
+    "LOTW_QSL_RCVD" Enumeration: Enumeration(Enumeration::QslRcvd),
+    "LOTW_QSL_SENT" Enumeration: Enumeration(Enumeration::QslSent),
+    "MAX_BURSTS" Number: Range(0.0, f64::MAX),
+    "MODE" Enumeration: Enumeration(Enumeration::Mode),
+    "MS_SHOWER" String,
+    "MY_ALTITUDE" Number,
+    "MY_ANTENNA" String,
+    "MY_ANTENNA_INTL" IntlString,
+    "MY_ARRL_SECT" Enumeration: Enumeration(Enumeration::ArrlSection),
+    "MY_CITY" String,
+    "MY_CITY_INTL" IntlString,
+    "MY_CNTY" Enumeration,
+    "MY_COUNTRY" String,
+    "MY_COUNTRY_INTL" IntlString,
+    "MY_CQ_ZONE" Number: Range(1.0, 40.0),
+    "MY_DXCC" Enumeration: Range(0.0, 522.0),
+    "MY_FISTS" Number: PositiveInteger,
+    "MY_GRIDSQUARE" String: GridSquare,
+    "MY_GRIDSQUARE_EXT" String,
+    "MY_IOTA" String,
+    "MY_IOTA_ISLAND_ID" Number: PositiveInteger,
+    "MY_ITU_ZONE" Number: Range(1.0, 90.0),
+    "MY_LAT" Location,
+    "MY_LON" Location,
+    "MY_NAME" String,
+    "MY_NAME_INTL" IntlString,
+    "MY_POSTAL_CODE" String,
+    "MY_POSTAL_CODE_INTL" IntlString,
+    "MY_POTA_REF" String,
+    "MY_RIG" String,
+    "MY_RIG_INTL" IntlString,
+    "MY_SIG" String,
+    "MY_SIG_INTL" IntlString,
+    "MY_SIG_INFO" String,
+    "MY_SIG_INFO_INTL" IntlString,
+    "MY_SOTA_REF" String,
+    "MY_STATE" Enumeration,
+    "MY_STREET" String,
+    "MY_STREET_INTL" IntlString,
+    "MY_USACA_COUNTIES" String,
+    "MY_VUCC_GRIDS" String,
+    "MY_WWFF_REF" String,
+    "NAME" String,
+    "NAME_INTL" IntlString,
+    "NOTES" MultilineString,
+    "NOTES_INTL" IntlMultilineString,
+    "NR_BURSTS" Number: Range(0.0, f64::MAX),
+    "NR_PINGS" Number: Range(0.0, f64::MAX),
+    "OPERATOR" String,
+    "OWNER_CALLSIGN" String,
+    "PFX" String,
+    "POTA_REF" String,
+    "PRECEDENCE" String,
+    "PROP_MODE" Enumeration: Enumeration(Enumeration::PropagationMode),
+    "PUBLIC_KEY" String,
+    "QRZCOM_QSO_UPLOAD_DATE" Date,
+    "QRZCOM_QSO_UPLOAD_STATUS" Enumeration: Enumeration(Enumeration::QsoUploadStatus),
+    "QSLMSG" MultilineString,
+    "QSLMSG_INTL" IntlMultilineString,
+    "QSLRDATE" Date,
+    "QSLSDATE" Date,
+    "QSL_RCVD" Enumeration: Enumeration(Enumeration::QslRcvd),
+    "QSL_RCVD_VIA" Enumeration: Enumeration(Enumeration::QslVia),
+    "QSL_SENT" Enumeration: Enumeration(Enumeration::QslSent),
+    "QSL_SENT_VIA" Enumeration: Enumeration(Enumeration::QslVia),
+    "QSL_VIA" String,
+    "QSO_COMPLETE" Enumeration: Enumeration(Enumeration::QsoComplete),
+    "QSO_DATE" Date,
+    "QSO_DATE_OFF" Date,
+    "QSO_RANDOM" Boolean,
+    "QTH" String,
+    "QTH_INTL" IntlString,
+    "REGION" Enumeration,
+    "RIG" MultilineString,
+    "RIG_INTL" IntlMultilineString,
+    "RST_RCVD" String,
+    "RST_SENT" String,
+    "RX_PWR" Number: Range(0.0, f64::MAX),
+    "SAT_MODE" String,
+    "SAT_NAME" String,
+    "SFI" Number: Range(0.0, 300.0),
+    "SIG" String,
+    "SIG_INTL" IntlString,
+    "SIG_INFO" String,
+    "SIG_INFO_INTL" IntlString,
+    "SILENT_KEY" Boolean,
+    "SKCC" String,
+    "SOTA_REF" String,
+    "SRX" Number: Range(0.0, f64::MAX),
+    "SRX_STRING" String,
+    "STATE" Enumeration,
+    "STATION_CALLSIGN" String,
+    "STX" Number: Range(0.0, f64::MAX),
+    "STX_STRING" String,
+    "SUBMODE" String: Enumeration(Enumeration::Submode),
+    "SWL" Boolean,
+    "TEN_TEN" Number: PositiveInteger,
+    "TIME_OFF" Time,
+    "TIME_ON" Time,
+    "TX_PWR" Number: Range(0.0, f64::MAX),
+    "UKSMG" Number: PositiveInteger,
+    "USACA_COUNTIES" String,
+    "VE_PROV" String,
+    "VUCC_GRIDS" String,
+    "WEB" String,
+    "WWFF_REF" String,
+];
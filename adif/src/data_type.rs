@@ -0,0 +1,134 @@
+/// ADIF data types that can appear as a type indicator in a data specifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Boolean,
+    Number,
+    Date,
+    Time,
+    String,
+    IntlString,
+    MultilineString,
+    IntlMultilineString,
+    Enumeration,
+    Location,
+}
+
+impl DataType {
+    pub fn from_indicator(indicator: char) -> Option<Self> {
+        match indicator.to_ascii_uppercase() {
+            'B' => Some(Self::Boolean),
+            'N' => Some(Self::Number),
+            'D' => Some(Self::Date),
+            'T' => Some(Self::Time),
+            'S' => Some(Self::String),
+            'I' => Some(Self::IntlString),
+            'M' => Some(Self::MultilineString),
+            'G' => Some(Self::IntlMultilineString),
+            'E' => Some(Self::Enumeration),
+            'L' => Some(Self::Location),
+            _ => None,
+        }
+    }
+
+    /// Gets the type of a field from its indicator, `_INTL` fields are always international
+    pub fn for_field(name: &str, indicator: char) -> Option<Self> {
+        let typ = Self::from_indicator(indicator)?;
+        let is_intl =
+            name.len() > 5 && name.as_bytes()[(name.len() - 5)..].eq_ignore_ascii_case(b"_intl");
+        Some(match typ {
+            Self::String if is_intl => Self::IntlString,
+            Self::MultilineString if is_intl => Self::IntlMultilineString,
+            t => t,
+        })
+    }
+
+    pub fn indicator(&self) -> char {
+        match self {
+            Self::Boolean => 'B',
+            Self::Number => 'N',
+            Self::Date => 'D',
+            Self::Time => 'T',
+            Self::String => 'S',
+            Self::IntlString => 'I',
+            Self::MultilineString => 'M',
+            Self::IntlMultilineString => 'G',
+            Self::Enumeration => 'E',
+            Self::Location => 'L',
+        }
+    }
+
+    /// Checks that `value` is valid for this type, empty values are always valid
+    pub fn is_valid(&self, value: &str) -> bool {
+        if value.is_empty() {
+            return true;
+        }
+
+        match self {
+            Self::Boolean => matches!(value, "Y" | "y" | "N" | "n"),
+            Self::Number => is_number(value),
+            Self::Date => is_date(value),
+            Self::Time => is_time(value),
+            Self::String | Self::Enumeration => value.bytes().all(|b| (32..=126).contains(&b)),
+            Self::MultilineString => value
+                .bytes()
+                .all(|b| (32..=126).contains(&b) || b == b'\r' || b == b'\n'),
+            Self::IntlString => !value.contains(['\r', '\n']),
+            Self::IntlMultilineString => true,
+            Self::Location => is_location(value),
+        }
+    }
+}
+
+impl core::fmt::Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
+
+fn all_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_number(value: &str) -> bool {
+    let value = value.strip_prefix('-').unwrap_or(value);
+    match value.split_once('.') {
+        Some((whole, fraction)) => {
+            (whole.is_empty() || all_digits(whole))
+                && (fraction.is_empty() || all_digits(fraction))
+                && !(whole.is_empty() && fraction.is_empty())
+        }
+        None => all_digits(value),
+    }
+}
+
+fn is_date(value: &str) -> bool {
+    if value.len() != 8 || !all_digits(value) {
+        return false;
+    }
+    let month: u8 = value[4..6].parse().unwrap_or(0);
+    let day: u8 = value[6..8].parse().unwrap_or(0);
+    (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+fn is_time(value: &str) -> bool {
+    if !matches!(value.len(), 4 | 6) || !all_digits(value) {
+        return false;
+    }
+    let hour: u8 = value[0..2].parse().unwrap_or(99);
+    let minute: u8 = value[2..4].parse().unwrap_or(99);
+    let second: u8 = value.get(4..6).map_or(0, |s| s.parse().unwrap_or(99));
+    hour < 24 && minute < 60 && second < 60
+}
+
+/// `XDDD MM.MMM` where `X` is one of `NSEW`
+fn is_location(value: &str) -> bool {
+    let b = value.as_bytes();
+    value.is_ascii()
+        && b.len() == 11
+        && matches!(b[0].to_ascii_uppercase(), b'N' | b'S' | b'E' | b'W')
+        && all_digits(&value[1..4])
+        && b[4] == b' '
+        && all_digits(&value[5..7])
+        && b[7] == b'.'
+        && all_digits(&value[8..11])
+}
@@ -0,0 +1,566 @@
+#[cfg(test)]
+mod test;
+
+/// ADIF enumerations that field values are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Enumeration {
+    AntPath,
+    ArrlSection,
+    Band,
+    Continent,
+    Mode,
+    PropagationMode,
+    QslRcvd,
+    QslSent,
+    QslVia,
+    QsoComplete,
+    QsoUploadStatus,
+    Submode,
+}
+
+impl Enumeration {
+    /// The allowed values, in upper case
+    pub fn values(&self) -> Vec<&'static str> {
+        match self {
+            Self::AntPath => ANT_PATHS.to_vec(),
+            Self::ArrlSection => ARRL_SECTIONS.to_vec(),
+            Self::Band => BANDS.iter().map(|b| b.name).collect(),
+            Self::Continent => CONTINENTS.to_vec(),
+            Self::Mode => MODES.iter().map(|m| m.0).collect(),
+            Self::PropagationMode => PROPAGATION_MODES.to_vec(),
+            Self::QslRcvd => QSL_RCVD.to_vec(),
+            Self::QslSent => QSL_SENT.to_vec(),
+            Self::QslVia => QSL_VIA.to_vec(),
+            Self::QsoComplete => QSO_COMPLETE.to_vec(),
+            Self::QsoUploadStatus => QSO_UPLOAD_STATUS.to_vec(),
+            Self::Submode => MODES.iter().flat_map(|m| m.1).copied().collect(),
+        }
+    }
+
+    /// Checks `value` against the enumeration, ignoring case
+    pub fn contains(&self, value: &str) -> bool {
+        match self {
+            Self::Band => band(value).is_some(),
+            Self::Mode => MODES.iter().any(|m| m.0.eq_ignore_ascii_case(value)),
+            Self::Submode => mode_for_submode(value).is_some(),
+            e => e.values().iter().any(|v| v.eq_ignore_ascii_case(value)),
+        }
+    }
+}
+
+impl core::fmt::Display for Enumeration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
+
+/// An amateur band and its edges in MHz
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandInfo {
+    pub name: &'static str,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl BandInfo {
+    pub fn contains(&self, mhz: f64) -> bool {
+        (self.lower..=self.upper).contains(&mhz)
+    }
+}
+
+/// Finds a band by name, ignoring case
+pub fn band(name: &str) -> Option<&'static BandInfo> {
+    BANDS.iter().find(|b| b.name.eq_ignore_ascii_case(name))
+}
+
+/// Finds the band a frequency in MHz is in
+pub fn band_for_frequency(mhz: f64) -> Option<&'static BandInfo> {
+    BANDS.iter().find(|b| b.contains(mhz))
+}
+
+/// The submodes of a mode, or `None` if it is not a mode
+pub fn submodes(mode: &str) -> Option<&'static [&'static str]> {
+    MODES
+        .iter()
+        .find(|m| m.0.eq_ignore_ascii_case(mode))
+        .map(|m| m.1)
+}
+
+/// The mode a submode belongs to
+pub fn mode_for_submode(submode: &str) -> Option<&'static str> {
+    MODES
+        .iter()
+        .find(|m| m.1.iter().any(|s| s.eq_ignore_ascii_case(submode)))
+        .map(|m| m.0)
+}
+
+macro_rules! bands {
+    ($($name:literal $lower:literal $upper:literal,)*) => {
+        &[$(BandInfo { name: $name, lower: $lower, upper: $upper },)*]
+    };
+}
+
+pub static BANDS: &[BandInfo] = bands![
+    "2190m" 0.1357 0.1378,
+    "630m" 0.472 0.479,
+    "560m" 0.501 0.504,
+    "160m" 1.8 2.0,
+    "80m" 3.5 4.0,
+    "60m" 5.06 5.45,
+    "40m" 7.0 7.3,
+    "30m" 10.1 10.15,
+    "20m" 14.0 14.35,
+    "17m" 18.068 18.168,
+    "15m" 21.0 21.45,
+    "12m" 24.890 24.99,
+    "10m" 28.0 29.7,
+    "8m" 40.0 45.0,
+    "6m" 50.0 54.0,
+    "5m" 54.000001 69.9,
+    "4m" 70.0 71.0,
+    "2m" 144.0 148.0,
+    "1.25m" 222.0 225.0,
+    "70cm" 420.0 450.0,
+    "33cm" 902.0 928.0,
+    "23cm" 1240.0 1300.0,
+    "13cm" 2300.0 2450.0,
+    "9cm" 3300.0 3500.0,
+    "6cm" 5650.0 5925.0,
+    "3cm" 10000.0 10500.0,
+    "1.25cm" 24000.0 24250.0,
+    "6mm" 47000.0 47200.0,
+    "4mm" 75500.0 81000.0,
+    "2.5mm" 119980.0 123000.0,
+    "2mm" 134000.0 149000.0,
+    "1mm" 241000.0 250000.0,
+    "submm" 300000.0 7500000.0,
+];
+
+/// Modes and their submodes
+pub static MODES: &[(&str, &[&str])] = &[
+    ("AM", &[]),
+    ("ARDOP", &[]),
+    ("ATV", &[]),
+    ("CHIP", &["CHIP64", "CHIP128"]),
+    ("CLO", &[]),
+    ("CONTESTI", &[]),
+    ("CW", &["PCW"]),
+    ("DIGITALVOICE", &["C4FM", "DMR", "DSTAR", "FREEDV", "M17"]),
+    (
+        "DOMINO",
+        &[
+            "DOM-M", "DOM4", "DOM5", "DOM8", "DOM11", "DOM16", "DOM22", "DOM44", "DOM88",
+            "DOMINOEX", "DOMINOF",
+        ],
+    ),
+    (
+        "DYNAMIC",
+        &["VARA HF", "VARA SATELLITE", "VARA FM 1200", "VARA FM 9600"],
+    ),
+    ("FAX", &[]),
+    ("FM", &[]),
+    ("FSK441", &[]),
+    ("FT8", &[]),
+    (
+        "HELL",
+        &[
+            "FMHELL", "FSKHELL", "HELL80", "HELLX5", "HELLX9", "HFSK", "PSKHELL", "SLOWHELL",
+        ],
+    ),
+    ("ISCAT", &["ISCAT-A", "ISCAT-B"]),
+    (
+        "JT4",
+        &["JT4A", "JT4B", "JT4C", "JT4D", "JT4E", "JT4F", "JT4G"],
+    ),
+    ("JT6M", &[]),
+    (
+        "JT9",
+        &[
+            "JT9-1",
+            "JT9-2",
+            "JT9-5",
+            "JT9-10",
+            "JT9-30",
+            "JT9A",
+            "JT9B",
+            "JT9C",
+            "JT9D",
+            "JT9E",
+            "JT9E FAST",
+            "JT9F",
+            "JT9F FAST",
+            "JT9G",
+            "JT9G FAST",
+            "JT9H",
+            "JT9H FAST",
+        ],
+    ),
+    ("JT44", &[]),
+    ("JT65", &["JT65A", "JT65B", "JT65B2", "JT65C", "JT65C2"]),
+    (
+        "MFSK",
+        &[
+            "FSQCALL", "FST4", "FST4W", "FT4", "JS8", "JTMS", "MFSK4", "MFSK8", "MFSK11", "MFSK16",
+            "MFSK22", "MFSK31", "MFSK32", "MFSK64", "MFSK64L", "MFSK128", "MFSK128L", "Q65",
+        ],
+    ),
+    ("MSK144", &[]),
+    ("MT63", &[]),
+    (
+        "OLIVIA",
+        &[
+            "OLIVIA 4/125",
+            "OLIVIA 4/250",
+            "OLIVIA 8/250",
+            "OLIVIA 8/500",
+            "OLIVIA 16/500",
+            "OLIVIA 16/1000",
+            "OLIVIA 32/1000",
+        ],
+    ),
+    ("OPERA", &["OPERA-BEACON", "OPERA-QSO"]),
+    ("PAC", &["PAC2", "PAC3", "PAC4"]),
+    ("PAX", &["PAX2"]),
+    ("PKT", &[]),
+    (
+        "PSK",
+        &[
+            "8PSK125",
+            "8PSK125F",
+            "8PSK125FL",
+            "8PSK250",
+            "8PSK250F",
+            "8PSK250FL",
+            "8PSK500",
+            "8PSK500F",
+            "8PSK1000",
+            "8PSK1000F",
+            "8PSK1200F",
+            "FSK31",
+            "PSK10",
+            "PSK31",
+            "PSK63",
+            "PSK63F",
+            "PSK63RC4",
+            "PSK63RC5",
+            "PSK63RC10",
+            "PSK63RC20",
+            "PSK63RC32",
+            "PSK125",
+            "PSK125C12",
+            "PSK125R",
+            "PSK125RC10",
+            "PSK125RC12",
+            "PSK125RC16",
+            "PSK125RC4",
+            "PSK125RC5",
+            "PSK250",
+            "PSK250C6",
+            "PSK250R",
+            "PSK250RC2",
+            "PSK250RC3",
+            "PSK250RC5",
+            "PSK250RC6",
+            "PSK250RC7",
+            "PSK500",
+            "PSK500C2",
+            "PSK500C4",
+            "PSK500R",
+            "PSK500RC2",
+            "PSK500RC3",
+            "PSK500RC4",
+            "PSK800C2",
+            "PSK800RC2",
+            "PSK1000",
+            "PSK1000C2",
+            "PSK1000R",
+            "PSK1000RC2",
+            "PSKAM10",
+            "PSKAM31",
+            "PSKAM50",
+            "PSKFEC31",
+            "QPSK31",
+            "QPSK63",
+            "QPSK125",
+            "QPSK250",
+            "QPSK500",
+            "SIM31",
+        ],
+    ),
+    ("PSK2K", &[]),
+    ("Q15", &[]),
+    ("QRA64", &["QRA64A", "QRA64B", "QRA64C", "QRA64D", "QRA64E"]),
+    ("ROS", &["ROS-EME", "ROS-HF", "ROS-MF"]),
+    ("RTTY", &["ASCI"]),
+    ("RTTYM", &[]),
+    ("SSB", &["LSB", "USB"]),
+    ("SSTV", &[]),
+    ("T10", &[]),
+    (
+        "THOR",
+        &[
+            "THOR-M", "THOR4", "THOR5", "THOR8", "THOR11", "THOR16", "THOR22", "THOR25X4",
+            "THOR50X1", "THOR50X2", "THOR100",
+        ],
+    ),
+    (
+        "THRB",
+        &[
+            "THRBX", "THRBX1", "THRBX2", "THRBX4", "THROB1", "THROB2", "THROB4",
+        ],
+    ),
+    ("TOR", &["AMTORFEC", "GTOR", "NAVTEX", "SITORB"]),
+    ("V4", &[]),
+    ("VOI", &[]),
+    ("WINMOR", &[]),
+    ("WSPR", &[]),
+];
+
+pub static CONTINENTS: &[&str] = &["NA", "SA", "EU", "AF", "OC", "AS", "AN"];
+
+pub static ARRL_SECTIONS: &[&str] = &[
+    "AB", "AK", "AL", "AR", "AZ", "BC", "CO", "CT", "DE", "EB", "EMA", "ENY", "EPA", "EWA", "GA",
+    "GH", "GTA", "IA", "ID", "IL", "IN", "KS", "KY", "LA", "LAX", "MAR", "MB", "MDC", "ME", "MI",
+    "MN", "MO", "MS", "MT", "NB", "NC", "ND", "NE", "NFL", "NH", "NL", "NLI", "NM", "NNJ", "NNY",
+    "NS", "NT", "NTX", "NV", "NWT", "OH", "OK", "ON", "ONE", "ONN", "ONS", "OR", "ORG", "PAC",
+    "PE", "PR", "QC", "RI", "SB", "SC", "SCV", "SD", "SDG", "SF", "SFL", "SJV", "SK", "SNJ", "STX",
+    "SV", "TER", "TN", "UT", "VA", "VI", "VT", "WCF", "WI", "WMA", "WNY", "WPA", "WTX", "WV",
+    "WWA", "WY",
+];
+
+pub static ANT_PATHS: &[&str] = &["G", "O", "S", "L"];
+
+pub static PROPAGATION_MODES: &[&str] = &[
+    "AS", "AUE", "AUR", "BS", "ECH", "EME", "ES", "F2", "FAI", "GWAVE", "INTERNET", "ION", "IRL",
+    "LOS", "MS", "RPT", "RS", "SAT", "TEP", "TR",
+];
+
+pub static QSL_RCVD: &[&str] = &["Y", "N", "R", "I", "V"];
+pub static QSL_SENT: &[&str] = &["Y", "N", "R", "Q", "I"];
+pub static QSL_VIA: &[&str] = &["B", "D", "E", "M"];
+pub static QSO_COMPLETE: &[&str] = &["Y", "N", "NIL", "?"];
+pub static QSO_UPLOAD_STATUS: &[&str] = &["Y", "N", "M"];
+
+/// Defines an enum for an ADIF enumeration, values are matched ignoring case and anything
+/// else is kept as `Unknown`
+macro_rules! typed_enumeration {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            /// A value that is not in the enumeration, as it was read
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Unknown(s) => s,
+                }
+            }
+
+            pub fn is_known(&self) -> bool {
+                !matches!(self, Self::Unknown(_))
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                $(if value.eq_ignore_ascii_case($value) {
+                    return Self::$variant;
+                })*
+                Self::Unknown(value.to_owned())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match Self::from(value.as_str()) {
+                    Self::Unknown(_) => Self::Unknown(value),
+                    known => known,
+                }
+            }
+        }
+
+        impl core::str::FromStr for $name {
+            type Err = core::convert::Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(Self::from(s))
+            }
+        }
+
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct Visitor;
+                impl<'de> serde::de::Visitor<'de> for Visitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str(concat!("a ", stringify!($name), " name"))
+                    }
+
+                    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                        Ok($name::from(v))
+                    }
+
+                    fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+                        Ok($name::from(v))
+                    }
+                }
+
+                deserializer.deserialize_str(Visitor)
+            }
+        }
+    };
+}
+
+typed_enumeration! {
+    /// A band from the ADIF Band enumeration
+    Band {
+        M2190 => "2190m",
+        M630 => "630m",
+        M560 => "560m",
+        M160 => "160m",
+        M80 => "80m",
+        M60 => "60m",
+        M40 => "40m",
+        M30 => "30m",
+        M20 => "20m",
+        M17 => "17m",
+        M15 => "15m",
+        M12 => "12m",
+        M10 => "10m",
+        M8 => "8m",
+        M6 => "6m",
+        M5 => "5m",
+        M4 => "4m",
+        M2 => "2m",
+        M1_25 => "1.25m",
+        Cm70 => "70cm",
+        Cm33 => "33cm",
+        Cm23 => "23cm",
+        Cm13 => "13cm",
+        Cm9 => "9cm",
+        Cm6 => "6cm",
+        Cm3 => "3cm",
+        Cm1_25 => "1.25cm",
+        Mm6 => "6mm",
+        Mm4 => "4mm",
+        Mm2_5 => "2.5mm",
+        Mm2 => "2mm",
+        Mm1 => "1mm",
+        Submm => "submm",
+    }
+}
+
+impl Band {
+    /// Finds the band a frequency in MHz is in
+    pub fn from_frequency(mhz: f64) -> Option<Self> {
+        band_for_frequency(mhz).map(|b| Self::from(b.name))
+    }
+
+    /// The band edges, `None` for unknown bands
+    pub fn info(&self) -> Option<&'static BandInfo> {
+        band(self.as_str()).filter(|_| self.is_known())
+    }
+}
+
+typed_enumeration! {
+    /// A mode from the ADIF Mode enumeration, submodes are not included
+    Mode {
+        Am => "AM",
+        Ardop => "ARDOP",
+        Atv => "ATV",
+        Chip => "CHIP",
+        Clo => "CLO",
+        Contesti => "CONTESTI",
+        Cw => "CW",
+        DigitalVoice => "DIGITALVOICE",
+        Domino => "DOMINO",
+        Dynamic => "DYNAMIC",
+        Fax => "FAX",
+        Fm => "FM",
+        Fsk441 => "FSK441",
+        Ft8 => "FT8",
+        Hell => "HELL",
+        Iscat => "ISCAT",
+        Jt4 => "JT4",
+        Jt6m => "JT6M",
+        Jt9 => "JT9",
+        Jt44 => "JT44",
+        Jt65 => "JT65",
+        Mfsk => "MFSK",
+        Msk144 => "MSK144",
+        Mt63 => "MT63",
+        Olivia => "OLIVIA",
+        Opera => "OPERA",
+        Pac => "PAC",
+        Pax => "PAX",
+        Pkt => "PKT",
+        Psk => "PSK",
+        Psk2k => "PSK2K",
+        Q15 => "Q15",
+        Qra64 => "QRA64",
+        Ros => "ROS",
+        Rtty => "RTTY",
+        Rttym => "RTTYM",
+        Ssb => "SSB",
+        Sstv => "SSTV",
+        T10 => "T10",
+        Thor => "THOR",
+        Thrb => "THRB",
+        Tor => "TOR",
+        V4 => "V4",
+        Voi => "VOI",
+        Winmor => "WINMOR",
+        Wspr => "WSPR",
+    }
+}
+
+impl Mode {
+    /// The mode a submode such as `USB` or `FT4` belongs to
+    pub fn from_submode(submode: &str) -> Option<Self> {
+        mode_for_submode(submode).map(Self::from)
+    }
+
+    pub fn submodes(&self) -> &'static [&'static str] {
+        submodes(self.as_str())
+            .filter(|_| self.is_known())
+            .unwrap_or_default()
+    }
+}
+
+typed_enumeration! {
+    /// A continent from the ADIF Continent enumeration
+    Continent {
+        NorthAmerica => "NA",
+        SouthAmerica => "SA",
+        Europe => "EU",
+        Africa => "AF",
+        Oceania => "OC",
+        Asia => "AS",
+        Antarctica => "AN",
+    }
+}
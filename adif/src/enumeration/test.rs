@@ -0,0 +1,60 @@
+use super::{Band, Continent, Mode};
+use crate::reader::Reader;
+
+#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Record {
+    band: Band,
+    mode: Mode,
+    cont: Option<Continent>,
+}
+
+#[test]
+fn typed() {
+    assert_eq!(Band::from("20M"), Band::M20);
+    assert_eq!(Band::from("1.25cm"), Band::Cm1_25);
+    assert_eq!(Band::from("21m"), Band::Unknown("21m".to_owned()));
+    assert_eq!(Band::from_frequency(3.5), Some(Band::M80));
+    assert_eq!(Band::M20.info().unwrap().upper, 14.35);
+    assert!(Band::Unknown("20m".to_owned()).info().is_none());
+
+    assert_eq!("ft8".parse(), Ok(Mode::Ft8));
+    assert_eq!(Mode::from_submode("usb"), Some(Mode::Ssb));
+    assert_eq!(Mode::Ssb.submodes(), &["LSB", "USB"]);
+    assert!(Mode::Unknown("SSB".to_owned()).submodes().is_empty());
+
+    assert_eq!(Continent::from("eu"), Continent::Europe);
+    assert_eq!(Continent::Oceania.to_string(), "OC");
+}
+
+#[test]
+fn serde() {
+    let input = "<BAND:3>40M <MODE:2>cw <CONT:2>na <EOR>
+<BAND:3>21m <MODE:4>MFSK <CONT:0> <EOR>";
+    let records: Vec<Record> = Reader::from_str(input)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        records,
+        vec![
+            Record {
+                band: Band::M40,
+                mode: Mode::Cw,
+                cont: Some(Continent::NorthAmerica),
+            },
+            Record {
+                band: Band::Unknown("21m".to_owned()),
+                mode: Mode::Mfsk,
+                cont: Some(Continent::Unknown(String::new())),
+            },
+        ]
+    );
+
+    assert_eq!(
+        crate::writer::to_string(&records[0]).unwrap(),
+        "<BAND:3>40m <MODE:2>CW <CONT:2>NA <EOR>\n"
+    );
+}
@@ -0,0 +1,167 @@
+use crate::{
+    data_type::DataType,
+    reader::{parse_specifier, Error},
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Header<'de> {
+    /// Free form text before the first header field
+    pub preamble: &'de str,
+
+    pub adif_ver: Option<&'de str>,
+    pub program_id: Option<&'de str>,
+    pub program_version: Option<&'de str>,
+    /// `YYYYMMDD HHMMSS` in UTC
+    pub created_timestamp: Option<&'de str>,
+
+    pub user_defined_fields: Vec<UserDefinedField<'de>>,
+
+    /// Any other fields, in the order they appeared
+    pub other_fields: Vec<(&'de str, &'de str)>,
+}
+
+/// A `USERDEFn` field definition from the header
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserDefinedField<'de> {
+    pub id: u32,
+    pub name: &'de str,
+    pub data_type: Option<DataType>,
+    pub enumeration: Option<Vec<&'de str>>,
+    pub range: Option<(f64, f64)>,
+}
+
+impl<'de> Header<'de> {
+    /// Parses the header up to and including `<eoh>`, returning it and the rest of the input
+    pub(crate) fn parse(input: &'de str) -> Result<(Self, &'de str), Error> {
+        let mut header = Header::default();
+        let mut preamble_end = None;
+        let mut rest = input;
+
+        loop {
+            let Some(start) = rest.find('<') else {
+                return Err(Error::NoData);
+            };
+            let tag_start = input.len() - rest.len() + start;
+            let after = &rest[(start + 1)..];
+
+            let Some(end) = after.find('>') else {
+                return Err(Error::UnexpectedEndOfInput(
+                    "while looking for the end of header",
+                ));
+            };
+            let specifier = &after[..end];
+            let value_start = &after[(end + 1)..];
+
+            if specifier.eq_ignore_ascii_case("eoh") {
+                header.preamble = &input[..preamble_end.unwrap_or(tag_start)];
+                return Ok((header, value_start));
+            }
+
+            let specifier = match parse_specifier(specifier) {
+                Ok(Some(s)) => s,
+                // a stray `<` before any fields is just part of the preamble
+                Ok(None) | Err(_) if preamble_end.is_none() => {
+                    rest = after;
+                    continue;
+                }
+                Ok(None) => return Err(Error::OtherError("no colon in data specifier")),
+                Err(e) => return Err(e),
+            };
+
+            let Some(value) = value_start.get(..specifier.length) else {
+                return if specifier.length > value_start.len() {
+                    Err(Error::UnexpectedEndOfInput("while reading header field"))
+                } else {
+                    Err(Error::LengthSplitsCharacter(specifier.length))
+                };
+            };
+
+            preamble_end.get_or_insert(tag_start);
+            header.set_field(specifier.name, specifier.data_type, value)?;
+            rest = &value_start[specifier.length..];
+        }
+    }
+
+    fn set_field(
+        &mut self,
+        name: &'de str,
+        data_type: Option<char>,
+        value: &'de str,
+    ) -> Result<(), Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "adif_ver" => self.adif_ver = Some(value),
+            "programid" => self.program_id = Some(value),
+            "programversion" => self.program_version = Some(value),
+            "created_timestamp" => self.created_timestamp = Some(value),
+            n => match n.strip_prefix("userdef").map(str::parse::<u32>) {
+                Some(Ok(id)) => self
+                    .user_defined_fields
+                    .push(UserDefinedField::parse(id, data_type, value)?),
+                _ => self.other_fields.push((name, value)),
+            },
+        }
+        Ok(())
+    }
+
+    /// Finds a user defined field by name, ignoring case
+    pub fn user_defined_field(&self, name: &str) -> Option<&UserDefinedField<'de>> {
+        self.user_defined_fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl<'de> UserDefinedField<'de> {
+    fn parse(id: u32, data_type: Option<char>, value: &'de str) -> Result<Self, Error> {
+        let data_type = data_type
+            .map(|c| DataType::from_indicator(c).ok_or(Error::InvalidDataType(c)))
+            .transpose()?;
+
+        let (name, limits) = match value.split_once(',') {
+            Some((name, limits)) => {
+                let Some(limits) = limits
+                    .trim()
+                    .strip_prefix('{')
+                    .and_then(|l| l.strip_suffix('}'))
+                else {
+                    return Err(Error::OtherError(
+                        "user defined field limits must be enclosed in braces",
+                    ));
+                };
+                (name, Some(limits))
+            }
+            None => (value, None),
+        };
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::OtherError("user defined field has no name"));
+        }
+
+        let mut field = Self {
+            id,
+            name,
+            data_type,
+            enumeration: None,
+            range: None,
+        };
+
+        match (limits, data_type) {
+            (None, _) => {}
+            (Some(limits), Some(DataType::Number)) => {
+                let Some((min, max)) = limits.split_once(':') else {
+                    return Err(Error::OtherError("user defined field range has no colon"));
+                };
+                field.range = Some((min.trim().parse()?, max.trim().parse()?));
+            }
+            (Some(limits), _) => {
+                field.enumeration = Some(limits.split(',').map(str::trim).collect());
+            }
+        }
+
+        Ok(field)
+    }
+}
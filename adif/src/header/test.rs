@@ -0,0 +1,96 @@
+use super::{Header, UserDefinedField};
+use crate::data_type::DataType;
+use crate::reader::{Error, Reader};
+
+const HEADER: &str = "Generated on 2011-11-22 at 02:15:23Z for WN4AZY
+
+<adif_ver:5>3.1.4
+<created_timestamp:15>20111122 021523
+<programid:7>monolog
+<programversion:5>1.2.3
+<USERDEF1:3:N>EPC
+<USERDEF2:19:E>SweaterSize,{S,M,L}
+<USERDEF3:15:N>ShoeSize,{5:20}
+<APP_MONOLOG_COLOR:3>red
+<EOH>
+<CALL:4>W1AW <EOR>
+";
+
+#[test]
+fn fields() {
+    let reader = Reader::from_str(HEADER).unwrap();
+    let header = reader.header().unwrap();
+
+    assert_eq!(
+        header.preamble,
+        "Generated on 2011-11-22 at 02:15:23Z for WN4AZY\n\n"
+    );
+    assert_eq!(header.adif_ver, Some("3.1.4"));
+    assert_eq!(header.created_timestamp, Some("20111122 021523"));
+    assert_eq!(header.program_id, Some("monolog"));
+    assert_eq!(header.program_version, Some("1.2.3"));
+    assert_eq!(header.other_fields, vec![("APP_MONOLOG_COLOR", "red")]);
+}
+
+#[test]
+fn user_defined_fields() {
+    let reader = Reader::from_str(HEADER).unwrap();
+    let header = reader.header().unwrap();
+
+    assert_eq!(
+        header.user_defined_fields,
+        vec![
+            UserDefinedField {
+                id: 1,
+                name: "EPC",
+                data_type: Some(DataType::Number),
+                enumeration: None,
+                range: None,
+            },
+            UserDefinedField {
+                id: 2,
+                name: "SweaterSize",
+                data_type: Some(DataType::Enumeration),
+                enumeration: Some(vec!["S", "M", "L"]),
+                range: None,
+            },
+            UserDefinedField {
+                id: 3,
+                name: "ShoeSize",
+                data_type: Some(DataType::Number),
+                enumeration: None,
+                range: Some((5.0, 20.0)),
+            },
+        ]
+    );
+    assert_eq!(header.user_defined_field("shoesize").unwrap().id, 3);
+}
+
+#[test]
+fn preamble_only() {
+    let (header, rest) = Header::parse("N1MM export\n<EOH>\n<CALL:4>W1AW <EOR>").unwrap();
+    assert_eq!(header.preamble, "N1MM export\n");
+    assert_eq!(header.adif_ver, None);
+    assert_eq!(rest, "\n<CALL:4>W1AW <EOR>");
+}
+
+#[test]
+fn stray_bracket_in_preamble() {
+    let (header, _) = Header::parse("Exported by <me>\n<adif_ver:5>3.1.4 <eoh>").unwrap();
+    assert_eq!(header.preamble, "Exported by <me>\n");
+    assert_eq!(header.adif_ver, Some("3.1.4"));
+}
+
+#[test]
+fn missing_eoh() {
+    assert!(matches!(
+        Reader::from_str("Header\n<adif_ver:5>3.1.4\n"),
+        Err(Error::NoData)
+    ));
+}
+
+#[test]
+fn invalid_user_defined_field() {
+    assert!(Header::parse("x <USERDEF1:11:N>Size,{5-20} <eoh>").is_err());
+    assert!(Header::parse("x <USERDEF1:7:E>Size,SM <eoh>").is_err());
+}
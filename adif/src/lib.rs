@@ -1 +1,18 @@
+pub mod adx;
+pub mod catalogue;
+pub mod data_type;
+pub mod enumeration;
+pub mod header;
+pub mod location;
 pub mod reader;
+pub mod record;
+pub mod stream;
+pub mod validate;
+pub mod writer;
+
+#[cfg(test)]
+mod test;
+
+pub use enumeration::{Band, Continent, Mode};
+pub use location::{GridSquare, Location};
+pub use record::Record;
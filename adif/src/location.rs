@@ -0,0 +1,276 @@
+#[cfg(test)]
+mod test;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("`{0}` is not an `XDDD MM.MMM` location")]
+    InvalidLocation(String),
+
+    #[error("`{0}` is not a Maidenhead grid square")]
+    InvalidGridSquare(String),
+
+    #[error("coordinate `{0}` is out of range")]
+    OutOfRange(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hemisphere {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Hemisphere {
+    pub fn is_latitude(&self) -> bool {
+        matches!(self, Self::North | Self::South)
+    }
+
+    fn sign(&self) -> f64 {
+        match self {
+            Self::South | Self::West => -1.0,
+            Self::North | Self::East => 1.0,
+        }
+    }
+}
+
+/// A latitude or longitude in the ADIF `XDDD MM.MMM` format, such as `LAT` or `MY_LON`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub hemisphere: Hemisphere,
+    pub degrees: u8,
+    pub minutes: f64,
+}
+
+impl Location {
+    /// From a latitude in decimal degrees, positive is north
+    pub fn latitude(decimal: f64) -> Result<Self, Error> {
+        Self::from_decimal(decimal, 90.0, Hemisphere::North, Hemisphere::South)
+    }
+
+    /// From a longitude in decimal degrees, positive is east
+    pub fn longitude(decimal: f64) -> Result<Self, Error> {
+        Self::from_decimal(decimal, 180.0, Hemisphere::East, Hemisphere::West)
+    }
+
+    fn from_decimal(
+        decimal: f64,
+        limit: f64,
+        positive: Hemisphere,
+        negative: Hemisphere,
+    ) -> Result<Self, Error> {
+        if !(-limit..=limit).contains(&decimal) {
+            return Err(Error::OutOfRange(decimal));
+        }
+
+        // rounded to the thousandths of a minute the format can hold
+        let thousandths = (decimal.abs() * 60_000.0).round() as u32;
+        Ok(Self {
+            hemisphere: if decimal < 0.0 { negative } else { positive },
+            degrees: (thousandths / 60_000) as u8,
+            minutes: (thousandths % 60_000) as f64 / 1000.0,
+        })
+    }
+
+    /// Decimal degrees, negative for south and west
+    pub fn to_decimal(&self) -> f64 {
+        self.hemisphere.sign() * (self.degrees as f64 + self.minutes / 60.0)
+    }
+}
+
+impl core::str::FromStr for Location {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidLocation(s.to_owned());
+        if s.is_empty() || !crate::data_type::DataType::Location.is_valid(s) {
+            return Err(invalid());
+        }
+
+        let hemisphere = match s.as_bytes()[0].to_ascii_uppercase() {
+            b'N' => Hemisphere::North,
+            b'S' => Hemisphere::South,
+            b'E' => Hemisphere::East,
+            _ => Hemisphere::West,
+        };
+        let degrees: u8 = s[1..4].parse().map_err(|_| invalid())?;
+        let minutes: f64 = s[5..].parse().map_err(|_| invalid())?;
+        let limit = if hemisphere.is_latitude() { 90 } else { 180 };
+        if degrees > limit || minutes >= 60.0 || (degrees == limit && minutes > 0.0) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            hemisphere,
+            degrees,
+            minutes,
+        })
+    }
+}
+
+impl core::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hemisphere = match self.hemisphere {
+            Hemisphere::North => 'N',
+            Hemisphere::South => 'S',
+            Hemisphere::East => 'E',
+            Hemisphere::West => 'W',
+        };
+        write!(f, "{}{:03} {:06.3}", hemisphere, self.degrees, self.minutes)
+    }
+}
+
+/// A 2, 4, 6 or 8 character Maidenhead locator, such as `GRIDSQUARE`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridSquare(String);
+
+impl GridSquare {
+    /// The locator of a point with `len` characters
+    pub fn from_coordinates(latitude: f64, longitude: f64, len: usize) -> Result<Self, Error> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(Error::OutOfRange(latitude));
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(Error::OutOfRange(longitude));
+        }
+        if !matches!(len, 2 | 4 | 6 | 8) {
+            return Err(Error::InvalidGridSquare(format!("{} characters", len)));
+        }
+
+        // the north and east edges belong to the last square
+        let mut lat = (latitude + 90.0).min(180.0 - 1e-9);
+        let mut lng = (longitude + 180.0).min(360.0 - 1e-9);
+        let mut locator = String::with_capacity(len);
+        for (i, (divisions, base)) in [(18, b'A'), (10, b'0'), (24, b'a'), (10, b'0')]
+            .into_iter()
+            .take(len / 2)
+            .enumerate()
+        {
+            let (lat_size, lng_size) = cell_size(i);
+            let (lat_index, lng_index) = ((lat / lat_size) as u8, (lng / lng_size) as u8);
+            let (lat_index, lng_index) =
+                (lat_index.min(divisions - 1), lng_index.min(divisions - 1));
+            locator.push((base + lng_index) as char);
+            locator.push((base + lat_index) as char);
+            lat -= lat_index as f64 * lat_size;
+            lng -= lng_index as f64 * lng_size;
+        }
+        Ok(Self(locator))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The south west corner in decimal degrees as latitude and longitude
+    pub fn south_west(&self) -> (f64, f64) {
+        let mut corner = (-90.0, -180.0);
+        for (i, pair) in self.0.as_bytes().chunks(2).enumerate() {
+            let base = match i {
+                0 => b'A',
+                2 => b'a',
+                _ => b'0',
+            };
+            let (lat_size, lng_size) = cell_size(i);
+            corner.0 += (pair[1] - base) as f64 * lat_size;
+            corner.1 += (pair[0] - base) as f64 * lng_size;
+        }
+        corner
+    }
+
+    /// The centre of the square in decimal degrees as latitude and longitude
+    pub fn center(&self) -> (f64, f64) {
+        let (lat_size, lng_size) = cell_size(self.0.len() / 2 - 1);
+        let (lat, lng) = self.south_west();
+        (lat + lat_size / 2.0, lng + lng_size / 2.0)
+    }
+}
+
+/// Height and width in degrees of the squares of each character pair
+fn cell_size(pair: usize) -> (f64, f64) {
+    match pair {
+        0 => (10.0, 20.0),
+        1 => (1.0, 2.0),
+        2 => (1.0 / 24.0, 2.0 / 24.0),
+        _ => (1.0 / 240.0, 2.0 / 240.0),
+    }
+}
+
+/// 2, 4, 6 or 8 character Maidenhead locator
+pub(crate) fn is_grid_square(value: &str) -> bool {
+    let b = value.as_bytes();
+    matches!(b.len(), 2 | 4 | 6 | 8)
+        && b.iter().enumerate().all(|(i, c)| match i {
+            0 | 1 => matches!(c.to_ascii_uppercase(), b'A'..=b'R'),
+            2 | 3 | 6 | 7 => c.is_ascii_digit(),
+            _ => matches!(c.to_ascii_uppercase(), b'A'..=b'X'),
+        })
+}
+
+impl core::str::FromStr for GridSquare {
+    type Err = Error;
+
+    /// Field letters are made upper case and subsquare letters lower case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !is_grid_square(s) {
+            return Err(Error::InvalidGridSquare(s.to_owned()));
+        }
+
+        let mut locator = s.to_owned();
+        locator[..2].make_ascii_uppercase();
+        if let Some(subsquare) = locator.get_mut(4..6) {
+            subsquare.make_ascii_lowercase();
+        }
+        Ok(Self(locator))
+    }
+}
+
+impl core::fmt::Display for GridSquare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Serialize as the ADIF text and deserialize by parsing it
+macro_rules! serde_from_str {
+    ($($name:ident: $expecting:literal,)*) => {
+        $(
+            impl serde::Serialize for $name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    struct Visitor;
+                    impl<'de> serde::de::Visitor<'de> for Visitor {
+                        type Value = $name;
+
+                        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                            formatter.write_str($expecting)
+                        }
+
+                        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                            v.parse().map_err(E::custom)
+                        }
+                    }
+
+                    deserializer.deserialize_str(Visitor)
+                }
+            }
+        )*
+    };
+}
+
+serde_from_str! {
+    Location: "an `XDDD MM.MMM` location",
+    GridSquare: "a Maidenhead grid square",
+}
@@ -0,0 +1,105 @@
+use super::{Error, GridSquare, Hemisphere, Location};
+use crate::reader::Reader;
+
+#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+struct Station {
+    lat: Location,
+    lon: Location,
+    gridsquare: GridSquare,
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+#[test]
+fn location() {
+    let lat: Location = "N041 42.882".parse().unwrap();
+    assert_eq!(lat.hemisphere, Hemisphere::North);
+    assert_eq!(lat.degrees, 41);
+    assert!(close(lat.to_decimal(), 41.7147));
+
+    let lon: Location = "w072 43.623".parse().unwrap();
+    assert!(close(lon.to_decimal(), -72.72705));
+    assert_eq!(lon.to_string(), "W072 43.623");
+
+    assert_eq!(
+        Location::latitude(-33.5).unwrap().to_string(),
+        "S033 30.000"
+    );
+    assert_eq!(Location::longitude(0.0).unwrap().to_string(), "E000 00.000");
+    // rounding up to a whole degree carries into the degrees
+    assert_eq!(
+        Location::longitude(9.9999999).unwrap().to_string(),
+        "E010 00.000"
+    );
+
+    assert_eq!(Location::latitude(91.0), Err(Error::OutOfRange(91.0)));
+    for invalid in [
+        "",
+        "N41 42.882",
+        "N091 00.000",
+        "E180 00.001",
+        "N041 60.000",
+        "X041 42.882",
+    ] {
+        assert!(invalid.parse::<Location>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn grid_square() {
+    let grid: GridSquare = "fn31PR".parse().unwrap();
+    assert_eq!(grid.as_str(), "FN31pr");
+
+    let (lat, lng) = grid.south_west();
+    assert!(close(lat, 41.708333) && close(lng, -72.75));
+    let (lat, lng) = grid.center();
+    assert!(close(lat, 41.729167) && close(lng, -72.708333));
+
+    let (lat, lng) = "JO".parse::<GridSquare>().unwrap().center();
+    assert!(close(lat, 55.0) && close(lng, 10.0));
+
+    assert_eq!(
+        GridSquare::from_coordinates(41.714775, -72.727260, 6).unwrap(),
+        grid
+    );
+    assert_eq!(
+        GridSquare::from_coordinates(90.0, 180.0, 8)
+            .unwrap()
+            .as_str(),
+        "RR99xx99"
+    );
+    assert_eq!(
+        GridSquare::from_coordinates(-90.0, -180.0, 4)
+            .unwrap()
+            .as_str(),
+        "AA00"
+    );
+    assert!(GridSquare::from_coordinates(0.0, 0.0, 5).is_err());
+    assert!("FN3".parse::<GridSquare>().is_err());
+    assert!("SN31".parse::<GridSquare>().is_err());
+}
+
+#[test]
+fn serde() {
+    let input = "<LAT:11>N041 42.882<LON:11>W072 43.623<GRIDSQUARE:6>FN31PR<EOR>";
+    let stations: Vec<Station> = Reader::from_str(input)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        crate::writer::to_string(&stations[0]).unwrap(),
+        "<LAT:11>N041 42.882 <LON:11>W072 43.623 <GRIDSQUARE:6>FN31pr <EOR>\n"
+    );
+
+    let invalid = "<LAT:11>N041 42.882<LON:3>W72<GRIDSQUARE:6>FN31PR<EOR>";
+    let result: Result<Vec<Station>, _> = Reader::from_str(invalid)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect();
+    assert!(result.is_err());
+}
@@ -1,6 +1,12 @@
+use crate::{data_type::DataType, header::Header, record::RECORD_STRUCT_NAME};
+
+#[cfg(test)]
+mod test;
+
 #[derive(Debug)]
 pub struct Reader<'de> {
-    header: Option<&'de str>,
+    input: &'de str,
+    header: Option<Header<'de>>,
     records: &'de str,
 }
 
@@ -9,35 +15,28 @@ impl<'de> Reader<'de> {
     pub fn from_str(input: &'de str) -> Result<Self, Error> {
         if input.starts_with('<') {
             return Ok(Reader {
+                input,
                 header: None,
                 records: input,
             });
         }
 
-        while let Some(i) = input.find('<') {
-            if Some("eoh>")
-                == input
-                    .get((i + 1)..(i + 5))
-                    .map(|t| t.to_ascii_lowercase())
-                    .as_deref()
-            {
-                return Ok(Reader {
-                    header: Some(&input[..i]),
-                    records: &input[i + 6..],
-                });
-            }
-        }
-        Err(Error::NoData)
+        let (header, records) = Header::parse(input)?;
+        Ok(Reader {
+            input,
+            header: Some(header),
+            records,
+        })
     }
 
-    pub fn header(&self) -> Option<&'de str> {
-        self.header
+    pub fn header(&self) -> Option<&Header<'de>> {
+        self.header.as_ref()
     }
 
     pub fn deserialize<D: serde::Deserialize<'de>>(
         &self,
     ) -> Result<DeserializeRecordsIter<'de, D>, Error> {
-        Ok(DeserializeRecordsIter::new(self.records))
+        Ok(DeserializeRecordsIter::new(self.input, self.records))
     }
 }
 
@@ -62,13 +61,155 @@ pub enum Error {
     #[error("issue parsing float: `0`")]
     ParseFloatError(core::num::ParseFloatError),
 
+    #[error("unknown data type indicator `{0}`")]
+    InvalidDataType(char),
+
+    #[error("value is not a valid `{0}`")]
+    InvalidValue(DataType),
+
+    #[error("length `{0}` ends in the middle of a utf-8 character")]
+    LengthSplitsCharacter(usize),
+
+    #[error("io error: `{0}`")]
+    Io(std::io::Error),
+
+    #[error("invalid ADX: `{0}`")]
+    Xml(#[from] quick_xml::Error),
+
+    #[error("record is longer than the limit of `{0}` bytes")]
+    RecordTooLong(usize),
+
     #[error("`{0}`")]
     OtherError(&'static str),
 
+    #[error("{0}")]
+    Positioned(Box<Diagnostic>),
+
     #[error("`{0}`")]
     Custom(String),
 }
 
+impl Error {
+    pub(crate) fn at(self, position: Position) -> Self {
+        Self::Positioned(Box::new(Diagnostic {
+            position,
+            error: self,
+        }))
+    }
+
+    /// Where in the input the error happened, if known
+    pub fn position(&self) -> Option<&Position> {
+        match self {
+            Self::Positioned(d) => Some(&d.position),
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without any position information
+    pub fn kind(&self) -> &Error {
+        match self {
+            Self::Positioned(d) => d.error.kind(),
+            e => e,
+        }
+    }
+
+    pub fn into_inner(self) -> Error {
+        match self {
+            Self::Positioned(d) => d.error.into_inner(),
+            e => e,
+        }
+    }
+}
+
+/// Where in the input an error happened
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Position {
+    /// Index of the record, starting at zero
+    pub record: usize,
+    /// Byte offset from the start of the input
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub field: Option<String>,
+}
+
+impl core::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "record {} (line {}, column {})",
+            self.record, self.line, self.column
+        )?;
+        if let Some(ref field) = self.field {
+            write!(f, " in field `{}`", field)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error along with where it happened
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub position: Position,
+    pub error: Error,
+}
+
+impl core::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.position, self.error)
+    }
+}
+
+/// Tracks the line and column of a byte offset, both starting at one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cursor {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl Cursor {
+    pub fn advance(&mut self, text: &[u8]) {
+        self.offset += text.len();
+        for b in text {
+            if *b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if b & 0xC0 != 0x80 {
+                // only count the first byte of each character
+                self.column += 1;
+            }
+        }
+    }
+
+    pub fn position(&self, record: usize, field: Option<String>) -> Position {
+        Position {
+            record,
+            offset: self.offset,
+            line: self.line,
+            column: self.column,
+            field,
+        }
+    }
+}
+
+/// Finds the end of the next `<eor>`, ignoring case
+pub(crate) fn find_eor_end(text: &[u8]) -> Option<usize> {
+    text.windows(5)
+        .position(|w| w.eq_ignore_ascii_case(b"<eor>"))
+        .map(|i| i + 5)
+}
+
 impl serde::de::Error for Error {
     fn custom<T>(msg: T) -> Self
     where
@@ -84,25 +225,100 @@ impl From<core::num::ParseIntError> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
 impl From<core::num::ParseFloatError> for Error {
     fn from(value: core::num::ParseFloatError) -> Self {
         Self::ParseFloatError(value)
     }
 }
 
+/// A parsed `<name:length:type>` data specifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Specifier<'de> {
+    pub name: &'de str,
+    pub length: usize,
+    pub data_type: Option<char>,
+}
+
+/// Parses the text between `<` and `>`, returns `None` for tags without a length such as `eor`
+pub(crate) fn parse_specifier(specifier: &str) -> Result<Option<Specifier<'_>>, Error> {
+    let Some((name, length_str)) = specifier.split_once(':') else {
+        return Ok(None);
+    };
+
+    let (length_str, data_type) = match length_str.split_once(':') {
+        Some((length_str, type_str)) => {
+            let mut ts = type_str.chars();
+            let typ = ts.next();
+            if typ.is_none() || ts.next().is_some() {
+                return Err(Error::OtherError("invalid type specifier"));
+            }
+            (length_str, typ)
+        }
+        None => (length_str, None),
+    };
+
+    let Ok(length): Result<usize, _> = str::parse(length_str) else {
+        return Err(Error::OtherError(
+            "could not parse length from data specifier",
+        ));
+    };
+
+    Ok(Some(Specifier {
+        name,
+        length,
+        data_type,
+    }))
+}
+
 #[derive(Debug)]
 pub struct DeserializeRecordsIter<'de, D> {
+    input: &'de str,
     records: &'de str,
+    record: usize,
+    lenient: bool,
+    diagnostics: Vec<Diagnostic>,
     _priv: std::marker::PhantomData<D>,
 }
 
 impl<'de, D> DeserializeRecordsIter<'de, D> {
-    fn new(records: &'de str) -> Self {
+    fn new(input: &'de str, records: &'de str) -> Self {
         Self {
+            input,
             records,
+            record: 0,
+            lenient: false,
+            diagnostics: Vec::new(),
             _priv: std::marker::PhantomData,
         }
     }
+
+    /// Skips malformed records instead of returning errors, see [`Self::diagnostics`]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Errors for the records that were skipped in lenient mode
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn error_position(&self, field: Option<String>) -> Position {
+        let offset = self.input.len() - self.records.len();
+        let mut cursor = Cursor::default();
+        cursor.advance(&self.input.as_bytes()[..offset]);
+        cursor.position(self.record, field)
+    }
 }
 
 impl<'de, D> Iterator for DeserializeRecordsIter<'de, D>
@@ -111,27 +327,81 @@ where
 {
     type Item = Result<D, Error>;
     fn next(&mut self) -> Option<Self::Item> {
-        let mut deserializer = RecordDeserializer {
-            records: &mut self.records,
-            read_string: None,
-            characters_to_read: None,
-            next_type: None,
-        };
+        loop {
+            let e = match deserialize_record(&mut self.records) {
+                Ok(record) => {
+                    self.record += 1;
+                    return Some(Ok(record));
+                }
+                Err(RecordError {
+                    error: Error::NoData,
+                    ..
+                }) => return None,
+                Err(e) => e,
+            };
+
+            let position = self.error_position(e.field);
+            self.record += 1;
+
+            // skip the rest of the record so the next call doesn't return the same error
+            if !e.finished {
+                match find_eor_end(self.records.as_bytes()) {
+                    Some(end) => self.records = &self.records[end..],
+                    None => self.records = "",
+                }
+            }
 
-        let item = D::deserialize(&mut deserializer);
+            if !self.lenient {
+                return Some(Err(e.error.at(position)));
+            }
 
-        match item {
-            Err(Error::NoData) => None,
-            other => Some(other),
+            self.diagnostics.push(Diagnostic {
+                position,
+                error: e.error,
+            });
         }
     }
 }
 
+/// An error from a single record, and how far into the record it was
+pub(crate) struct RecordError {
+    pub error: Error,
+    pub field: Option<String>,
+    /// If the `<eor>` was already read
+    pub finished: bool,
+}
+
+/// Deserializes the next record from the start of `records`, advancing past its `<eor>`
+pub(crate) fn deserialize_record<'de, D>(records: &mut &'de str) -> Result<D, RecordError>
+where
+    D: serde::Deserialize<'de>,
+{
+    let mut deserializer = RecordDeserializer {
+        records,
+        read_string: None,
+        characters_to_read: None,
+        next_type: None,
+        field: None,
+        finished: false,
+        raw_keys: false,
+    };
+
+    D::deserialize(&mut deserializer).map_err(|error| RecordError {
+        error,
+        field: deserializer.field,
+        finished: deserializer.finished,
+    })
+}
+
 struct RecordDeserializer<'de, 's> {
     records: &'s mut &'de str,
     read_string: Option<String>,
     characters_to_read: Option<usize>,
-    next_type: Option<char>,
+    next_type: Option<DataType>,
+    field: Option<String>,
+    finished: bool,
+    /// Give keys with their original case and type indicator, for [`crate::Record`]
+    raw_keys: bool,
 }
 
 impl<'de> RecordDeserializer<'de, '_> {
@@ -139,14 +409,34 @@ impl<'de> RecordDeserializer<'de, '_> {
         if self.read_string.is_some() {
             Err(Error::OtherError("unexpected deserializer state"))
         } else if let Some(length) = self.characters_to_read.take() {
-            let val = &self.records[..length];
-            *self.records = &self.records[length..];
-            Ok(val)
+            let val = self.peek_value(length)?;
+            match self.next_type.take() {
+                Some(typ) if !typ.is_valid(val) => Err(Error::InvalidValue(typ)),
+                _ => self.take_value(length),
+            }
         } else {
             Err(Error::InvalidType("str"))
         }
     }
 
+    /// Lengths are in bytes, so one that ends inside a character is an error rather than a panic
+    fn peek_value(&self, length: usize) -> Result<&'de str, Error> {
+        let records: &'de str = self.records;
+        match records.get(..length) {
+            Some(val) => Ok(val),
+            None if length > records.len() => {
+                Err(Error::UnexpectedEndOfInput("while reading value"))
+            }
+            None => Err(Error::LengthSplitsCharacter(length)),
+        }
+    }
+
+    fn take_value(&mut self, length: usize) -> Result<&'de str, Error> {
+        let val = self.peek_value(length)?;
+        *self.records = &self.records[length..];
+        Ok(val)
+    }
+
     fn get_char(&mut self) -> Result<char, Error> {
         let s = self.get_str()?;
         if s.len() > 1 {
@@ -169,8 +459,17 @@ impl<'de> serde::Deserializer<'de> for &mut RecordDeserializer<'de, '_> {
         if let Some(s) = self.read_string.take() {
             visitor.visit_string(s)
         } else if self.characters_to_read.is_some() {
-            Err(Error::InvalidType("any"))
-            // self.deserialize_str(visitor)
+            let typ = if self.raw_keys { None } else { self.next_type };
+            let s = self.get_str()?;
+            match typ {
+                _ if s.is_empty() => visitor.visit_borrowed_str(s),
+                Some(DataType::Number) => match s.parse::<i64>() {
+                    Ok(i) => visitor.visit_i64(i),
+                    Err(_) => visitor.visit_f64(s.parse()?),
+                },
+                Some(DataType::Boolean) => visitor.visit_bool(s.eq_ignore_ascii_case("y")),
+                _ => visitor.visit_borrowed_str(s),
+            }
         } else {
             self.deserialize_map(visitor)
         }
@@ -183,7 +482,8 @@ impl<'de> serde::Deserializer<'de> for &mut RecordDeserializer<'de, '_> {
         if self.read_string.take().is_some() {
             visitor.visit_none()
         } else if let Some(length) = self.characters_to_read.take() {
-            *self.records = &self.records[length..];
+            self.next_type = None;
+            self.take_value(length)?;
             visitor.visit_none()
         } else {
             self.deserialize_map(visitor)
@@ -201,8 +501,8 @@ impl<'de> serde::Deserializer<'de> for &mut RecordDeserializer<'de, '_> {
             "y" => Ok(true),
             "no" => Ok(false),
             "yes" => Ok(true),
-            "t" => Ok(false),
-            "f" => Ok(true),
+            "t" => Ok(true),
+            "f" => Ok(false),
             "false" => Ok(false),
             "true" => Ok(true),
             _ => Err(Error::InvalidType("bool")),
@@ -393,13 +693,16 @@ impl<'de> serde::Deserializer<'de> for &mut RecordDeserializer<'de, '_> {
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
+        if name == RECORD_STRUCT_NAME {
+            self.raw_keys = true;
+        }
         self.deserialize_map(visitor)
     }
 
@@ -407,12 +710,15 @@ impl<'de> serde::Deserializer<'de> for &mut RecordDeserializer<'de, '_> {
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        Err(Error::InvalidType("enum"))
+        // only unit variants can be represented, the value is the variant name
+        visitor.visit_enum(serde::de::value::BorrowedStrDeserializer::new(
+            self.get_str()?,
+        ))
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -422,7 +728,7 @@ impl<'de> serde::Deserializer<'de> for &mut RecordDeserializer<'de, '_> {
         if let Some(s) = self.read_string.take() {
             visitor.visit_string(s)
         } else {
-            todo!()
+            self.deserialize_str(visitor)
         }
     }
 }
@@ -440,8 +746,12 @@ impl<'de> serde::de::MapAccess<'de> for RecordDeserializer<'de, '_> {
 
         *self.records = self.records.trim_start();
         if !self.records.starts_with('<') {
-            if self.records.is_empty() {
+            if self.records.is_empty() && self.field.is_none() {
                 return Err(Error::NoData);
+            } else if self.records.is_empty() {
+                return Err(Error::UnexpectedEndOfInput(
+                    "while looking for end of record",
+                ));
             } else {
                 return Err(Error::OtherError("could not find start of data specifier"));
             }
@@ -454,41 +764,28 @@ impl<'de> serde::de::MapAccess<'de> for RecordDeserializer<'de, '_> {
 
         let specifier = &self.records[1..(idx + 1)]; // +1 since search was after first character
 
-        let Some(name_end_idx) = specifier.find(':') else {
-            if specifier.to_ascii_lowercase() == "eor" {
+        let Some(specifier) = parse_specifier(specifier)? else {
+            if specifier.eq_ignore_ascii_case("eor") {
                 *self.records = &self.records[(idx + 2)..];
+                self.finished = true;
                 return Ok(None);
             } else {
                 return Err(Error::OtherError("no colon in data specifier"));
             }
         };
 
-        let name = specifier[..name_end_idx].to_lowercase();
-
-        let mut length_str = &specifier[(name_end_idx + 1)..];
-        let data_type = if let Some(length_end_idx) = length_str.find(':') {
-            let mut ts = length_str[length_end_idx..].chars();
-            length_str = &length_str[..length_end_idx];
-
-            let typ = ts.next();
-            if ts.next().is_some() {
-                return Err(Error::OtherError("invalid type specifier"));
-            }
-            typ
-        } else {
-            None
-        };
-
-        let Ok(length): Result<usize, _> = str::parse(length_str) else {
-            return Err(Error::OtherError(
-                "could not parse length from data specifier",
-            ));
-        };
-
         *self.records = &self.records[(idx + 2)..];
-        self.read_string = Some(name);
-        self.characters_to_read = Some(length);
-        self.next_type = data_type;
+        self.field = Some(specifier.name.to_owned());
+        self.read_string = Some(match specifier.data_type {
+            Some(c) if self.raw_keys => format!("{}:{}", specifier.name, c),
+            None if self.raw_keys => specifier.name.to_owned(),
+            _ => specifier.name.to_lowercase(),
+        });
+        self.characters_to_read = Some(specifier.length);
+        self.next_type = specifier
+            .data_type
+            .map(|c| DataType::for_field(specifier.name, c).ok_or(Error::InvalidDataType(c)))
+            .transpose()?;
 
         Ok(Some(seed.deserialize(self)?))
     }
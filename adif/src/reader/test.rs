@@ -0,0 +1,188 @@
+use super::{Error, Reader};
+use crate::data_type::DataType;
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Record<'s> {
+    call: &'s str,
+    qso_date: Option<&'s str>,
+    freq: Option<f64>,
+    name_intl: Option<&'s str>,
+    lat: Option<&'s str>,
+    qsl_rcvd: Option<bool>,
+}
+
+fn read(input: &str) -> Result<Vec<Record<'_>>, Error> {
+    Reader::from_str(input)?
+        .deserialize()?
+        .collect::<Result<_, _>>()
+        .map_err(Error::into_inner)
+}
+
+#[test]
+fn type_indicators() {
+    let records = read(
+        "<CALL:4:S>W1AW <QSO_DATE:8:D>20231028 <FREQ:6:N>14.074 \
+         <LAT:11:L>N041 42.600 <QSL_RCVD:1:B>Y <EOR>",
+    )
+    .unwrap();
+    assert_eq!(
+        records,
+        vec![Record {
+            call: "W1AW",
+            qso_date: Some("20231028"),
+            freq: Some(14.074),
+            name_intl: None,
+            lat: Some("N041 42.600"),
+            qsl_rcvd: Some(true),
+        }]
+    );
+}
+
+#[test]
+fn invalid_values() {
+    assert!(matches!(
+        read("<CALL:4>W1AW <QSO_DATE:8:D>20231328 <EOR>"),
+        Err(Error::InvalidValue(DataType::Date))
+    ));
+    assert!(matches!(
+        read("<CALL:4>W1AW <FREQ:4:N>14.x <EOR>"),
+        Err(Error::InvalidValue(DataType::Number))
+    ));
+    assert!(matches!(
+        read("<CALL:4>W1AW <LAT:11:L>X041 42.600 <EOR>"),
+        Err(Error::InvalidValue(DataType::Location))
+    ));
+    assert!(matches!(
+        read("<CALL:4:S>WÅAW <EOR>"),
+        Err(Error::InvalidValue(DataType::String))
+    ));
+}
+
+#[test]
+fn unknown_type_indicator() {
+    assert!(matches!(
+        read("<CALL:4:Q>W1AW <EOR>"),
+        Err(Error::InvalidDataType('Q'))
+    ));
+    assert!(matches!(
+        read("<CALL:4:SS>W1AW <EOR>"),
+        Err(Error::OtherError(_))
+    ));
+}
+
+#[test]
+fn intl_fields() {
+    let records = read("<CALL:4>W1AW <NAME_INTL:7:S>Łukasz <EOR>").unwrap();
+    assert_eq!(records[0].name_intl, Some("Łukasz"));
+}
+
+#[test]
+fn length_splits_character() {
+    assert!(matches!(
+        read("<CALL:4>W1AW <NAME_INTL:3>ŁŁ <EOR>"),
+        Err(Error::LengthSplitsCharacter(3))
+    ));
+    assert!(matches!(
+        read("<CALL:4>W1AW <COMMENT:1>Ł <EOR>"),
+        Err(Error::LengthSplitsCharacter(1))
+    ));
+}
+
+#[test]
+fn truncated_value() {
+    assert!(matches!(
+        read("<CALL:40>W1AW"),
+        Err(Error::UnexpectedEndOfInput(_))
+    ));
+}
+
+#[test]
+fn error_position() {
+    let input = "header\n<eoh>\n<CALL:4>W1AW <EOR>\n<CALL:4>K9ET <FREQ:4:N>14.x <EOR>\n";
+    let err = Reader::from_str(input)
+        .unwrap()
+        .deserialize::<Record>()
+        .unwrap()
+        .nth(1)
+        .unwrap()
+        .unwrap_err();
+
+    assert!(matches!(err.kind(), Error::InvalidValue(DataType::Number)));
+    let position = err.position().unwrap();
+    assert_eq!(position.record, 1);
+    assert_eq!(position.line, 4);
+    assert_eq!(position.column, 24);
+    assert_eq!(position.field.as_deref(), Some("FREQ"));
+    assert_eq!(
+        err.to_string(),
+        "record 1 (line 4, column 24) in field `FREQ`: value is not a valid `Number`"
+    );
+}
+
+#[test]
+fn lenient() {
+    let input = "<CALL:4>W1AW <EOR>
+<CALL:4>K9ET <FREQ:4>14.x <EOR>
+<CALL:4>KD9M <FREQ 6>14.074 <EOR>
+<FREQ:1>7 <EOR>
+<CALL:6>JA1ABC <FREQ:1>7 <EOR>
+<CALL:4>DL1A <FREQ:3";
+
+    let mut records = Reader::from_str(input)
+        .unwrap()
+        .deserialize::<Record>()
+        .unwrap()
+        .lenient();
+    let calls: Vec<_> = (&mut records).map(|r| r.unwrap().call).collect();
+    assert_eq!(calls, vec!["W1AW", "JA1ABC"]);
+
+    let diagnostics = records.diagnostics();
+    assert_eq!(
+        diagnostics
+            .iter()
+            .map(|d| (d.position.record, d.position.line))
+            .collect::<Vec<_>>(),
+        vec![(1, 2), (2, 3), (3, 4), (5, 6)]
+    );
+    assert!(matches!(
+        diagnostics[3].error,
+        Error::UnexpectedEndOfInput(_)
+    ));
+}
+
+#[test]
+fn strict_continues_after_error() {
+    let input = "<CALL:4>K9ET <FREQ:4>14.x <EOR>\n<CALL:4>W1AW <EOR>";
+    let records: Vec<_> = Reader::from_str(input)
+        .unwrap()
+        .deserialize::<Record>()
+        .unwrap()
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert!(records[0].is_err());
+    assert_eq!(records[1].as_ref().unwrap().call, "W1AW");
+}
+
+#[test]
+fn unit_enums() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    enum QslRcvd {
+        Y,
+        N,
+        R,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Qsl {
+        qsl_rcvd: QslRcvd,
+    }
+
+    let records: Vec<Result<Qsl, _>> = Reader::from_str("<QSL_RCVD:1>R <EOR><QSL_RCVD:1>Q <EOR>")
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect();
+    assert_eq!(records[0].as_ref().unwrap().qsl_rcvd, QslRcvd::R);
+    assert!(records[1].is_err());
+}
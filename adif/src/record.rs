@@ -0,0 +1,263 @@
+use serde::{de, ser::SerializeMap, Deserialize, Serialize};
+
+use crate::{data_type::DataType, location::Location};
+
+#[cfg(test)]
+mod test;
+
+/// Struct name that asks the reader for keys with their original case and type indicator
+pub(crate) const RECORD_STRUCT_NAME: &str = "$adif::Record";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// Name as it appeared in the file
+    pub name: String,
+    pub data_type: Option<DataType>,
+    pub value: String,
+}
+
+/// Any ADIF record, with its fields in the order they were read
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    fields: Vec<Field>,
+}
+
+impl Record {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Field> {
+        self.fields.iter()
+    }
+
+    /// Finds a field by name, ignoring case
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.field(name).map(|f| f.value.as_str())
+    }
+
+    /// Sets a field, replacing it in place if it exists and appending it otherwise
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        data_type: Option<DataType>,
+        value: impl Into<String>,
+    ) -> Option<Field> {
+        let field = Field {
+            name: name.into(),
+            data_type,
+            value: value.into(),
+        };
+
+        match self
+            .fields
+            .iter_mut()
+            .find(|f| f.name.eq_ignore_ascii_case(&field.name))
+        {
+            Some(existing) => Some(std::mem::replace(existing, field)),
+            None => {
+                self.fields.push(field);
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Field> {
+        let idx = self
+            .fields
+            .iter()
+            .position(|f| f.name.eq_ignore_ascii_case(name))?;
+        Some(self.fields.remove(idx))
+    }
+
+    /// Reads a `YYYYMMDD` date field such as `QSO_DATE`
+    pub fn get_date(&self, name: &str) -> Option<time::Date> {
+        let value = self.get(name)?;
+        if value.len() != 8 || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let month: u8 = value[4..6].parse().ok()?;
+        time::Date::from_calendar_date(
+            value[0..4].parse().ok()?,
+            month.try_into().ok()?,
+            value[6..8].parse().ok()?,
+        )
+        .ok()
+    }
+
+    /// Reads a `HHMM` or `HHMMSS` time field such as `TIME_ON`
+    pub fn get_time(&self, name: &str) -> Option<time::Time> {
+        let value = self.get(name)?;
+        if !matches!(value.len(), 4 | 6) || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        time::Time::from_hms(
+            value[0..2].parse().ok()?,
+            value[2..4].parse().ok()?,
+            value.get(4..6).map_or(Some(0), |s| s.parse().ok())?,
+        )
+        .ok()
+    }
+
+    /// Reads a frequency field such as `FREQ`, which is in MHz, and returns it in Hz
+    pub fn get_freq(&self, name: &str) -> Option<i64> {
+        let mhz: f64 = self.get(name)?.trim().parse().ok()?;
+        mhz.is_finite().then(|| (mhz * 1000000.0).round() as i64)
+    }
+
+    /// Reads an `XDDD MM.MMM` location field such as `LAT`, in decimal degrees
+    pub fn get_location(&self, name: &str) -> Option<f64> {
+        self.get(name)?
+            .parse::<Location>()
+            .ok()
+            .map(|l| l.to_decimal())
+    }
+}
+
+impl<'a> IntoIterator for &'a Record {
+    type Item = &'a Field;
+    type IntoIter = std::slice::Iter<'a, Field>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fields.iter()
+    }
+}
+
+impl IntoIterator for Record {
+    type Item = Field;
+    type IntoIter = std::vec::IntoIter<Field>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fields.into_iter()
+    }
+}
+
+impl Serialize for Record {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for field in &self.fields {
+            map.serialize_entry(&field.name, &field.value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Record {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct RecordVisitor;
+        impl<'de> de::Visitor<'de> for RecordVisitor {
+            type Value = Record;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of ADIF fields")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut record = Record::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    let FieldValue(value) = map.next_value()?;
+
+                    // the reader gives keys as `name:type` when there is a type indicator
+                    let (name, data_type) = match key.split_once(':') {
+                        Some((name, indicator)) => {
+                            let mut chars = indicator.chars();
+                            let typ = match (chars.next(), chars.next()) {
+                                (Some(c), None) => DataType::from_indicator(c),
+                                _ => None,
+                            };
+                            match typ {
+                                Some(typ) => (name.to_owned(), Some(typ)),
+                                None => {
+                                    return Err(de::Error::invalid_value(
+                                        de::Unexpected::Str(&key),
+                                        &"a field name with an optional type indicator",
+                                    ))
+                                }
+                            }
+                        }
+                        None => (key, None),
+                    };
+
+                    record.fields.push(Field {
+                        name,
+                        data_type,
+                        value,
+                    });
+                }
+                Ok(record)
+            }
+        }
+
+        deserializer.deserialize_struct(RECORD_STRUCT_NAME, &[], RecordVisitor)
+    }
+}
+
+/// Accepts any scalar as a field value, so records can also come from other formats
+struct FieldValue(String);
+
+impl<'de> Deserialize<'de> for FieldValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct FieldValueVisitor;
+        impl<'de> de::Visitor<'de> for FieldValueVisitor {
+            type Value = FieldValue;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a string, number or boolean")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                Ok(FieldValue(if v { "Y" } else { "N" }.to_owned()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(FieldValue(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(FieldValue(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Ok(FieldValue(v.to_string()))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(FieldValue(v.to_owned()))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(FieldValue(v))
+            }
+        }
+
+        deserializer.deserialize_any(FieldValueVisitor)
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use super::Record;
+use crate::{data_type::DataType, reader::Reader, stream::StreamReader, writer::Writer};
+
+const INPUT: &str = "Log\n<EOH>\n<Call:4>W1AW <QSO_DATE:8:D>20231028 <TIME_ON:4>1432 \
+    <FREQ:6:N>14.074 <LAT:11:L>S041 30.000 <APP_N1MM_ID:3>abc <EOR>\n\
+    <CALL:4>K9ET <QSL_RCVD:1:B>Y <EOR>\n";
+
+fn read(input: &str) -> Vec<Record> {
+    Reader::from_str(input)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn fields() {
+    let records = read(INPUT);
+    assert_eq!(records.len(), 2);
+
+    let record = &records[0];
+    let names: Vec<_> = record.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["Call", "QSO_DATE", "TIME_ON", "FREQ", "LAT", "APP_N1MM_ID"]
+    );
+    assert_eq!(record.get("call"), Some("W1AW"));
+    assert_eq!(
+        record.field("qso_date").unwrap().data_type,
+        Some(DataType::Date)
+    );
+    assert_eq!(record.field("time_on").unwrap().data_type, None);
+    assert_eq!(record.get("app_n1mm_id"), Some("abc"));
+
+    assert_eq!(
+        record.get_date("qso_date"),
+        time::Date::from_calendar_date(2023, time::Month::October, 28).ok()
+    );
+    assert_eq!(
+        record.get_time("time_on"),
+        time::Time::from_hms(14, 32, 0).ok()
+    );
+    assert_eq!(record.get_freq("freq"), Some(14074000));
+    assert_eq!(record.get_location("lat"), Some(-41.5));
+    assert_eq!(record.get_date("call"), None);
+
+    assert_eq!(records[1].get("qsl_rcvd"), Some("Y"));
+}
+
+#[test]
+fn insert_remove() {
+    let mut record = Record::new();
+    assert!(record.insert("CALL", None, "W1AW").is_none());
+    assert!(record
+        .insert("FREQ", Some(DataType::Number), "7.1")
+        .is_none());
+    let old = record.insert("call", None, "K9ET").unwrap();
+
+    assert_eq!(old.value, "W1AW");
+    assert_eq!(record.iter().next().unwrap().name, "call");
+    assert_eq!(record.len(), 2);
+    assert_eq!(record.remove("Freq").unwrap().value, "7.1");
+    assert!(record.remove("freq").is_none());
+    assert_eq!(record.len(), 1);
+}
+
+#[test]
+fn deserialize_any() {
+    let maps: Vec<HashMap<String, String>> = Reader::from_str(INPUT)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(maps[0]["call"], "W1AW");
+    assert_eq!(maps[1]["qsl_rcvd"], "Y");
+
+    let values: Vec<serde_json::Value> = Reader::from_str(INPUT)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values[0]["freq"], serde_json::json!(14.074));
+    assert_eq!(values[0]["time_on"], serde_json::json!("1432"));
+    assert_eq!(values[1]["qsl_rcvd"], serde_json::json!(true));
+}
+
+#[test]
+fn stream() {
+    let records: Vec<Record> = StreamReader::new(INPUT.as_bytes())
+        .unwrap()
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records, read(INPUT));
+}
+
+#[test]
+fn round_trip() {
+    let records = read(INPUT);
+
+    let mut writer = Writer::new(Vec::new());
+    writer.write_header("Log", &()).unwrap();
+    for record in &records {
+        writer.write_record(record).unwrap();
+    }
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    assert!(output.contains("<QSO_DATE:8:D>20231028 "));
+    assert!(output.contains("<CALL:4>W1AW "));
+    // names are written in upper case
+    let mut expected = records;
+    expected[0].insert("CALL", None, "W1AW");
+    assert_eq!(read(&output), expected);
+}
@@ -0,0 +1,312 @@
+use std::io::BufRead;
+
+use crate::{
+    header::Header,
+    reader::{
+        deserialize_record, find_eor_end, parse_specifier, Cursor, Diagnostic, Error, Position,
+    },
+};
+
+#[cfg(test)]
+mod test;
+
+/// Default limit on the size of the header or of a single record
+pub const DEFAULT_MAX_RECORD_LENGTH: usize = 1 << 20;
+
+/// Reads ADI data incrementally from any [`BufRead`], only buffering one record at a time
+#[derive(Debug)]
+pub struct StreamReader<R> {
+    reader: R,
+    header: Option<String>,
+    buffer: Vec<u8>,
+    max_record_length: usize,
+}
+
+impl<R: BufRead> StreamReader<R> {
+    pub fn new(reader: R) -> Result<Self, Error> {
+        Self::with_max_record_length(reader, DEFAULT_MAX_RECORD_LENGTH)
+    }
+
+    pub fn with_max_record_length(mut reader: R, max_record_length: usize) -> Result<Self, Error> {
+        let (header, buffer) = read_header(&mut reader, max_record_length)?;
+        Ok(Self {
+            reader,
+            header,
+            buffer,
+            max_record_length,
+        })
+    }
+
+    pub fn header(&self) -> Option<Header<'_>> {
+        self.header.as_deref().map(|h| {
+            Header::parse(h)
+                .expect("header was validated when it was read")
+                .0
+        })
+    }
+
+    pub fn deserialize<D: serde::de::DeserializeOwned>(self) -> DeserializeRecordsStream<R, D> {
+        let mut cursor = Cursor::default();
+        if let Some(ref header) = self.header {
+            cursor.advance(header.as_bytes());
+        }
+
+        DeserializeRecordsStream {
+            reader: self.reader,
+            buffer: self.buffer,
+            cursor,
+            max_record_length: self.max_record_length,
+            record: 0,
+            lenient: false,
+            diagnostics: Vec::new(),
+            done: false,
+            _priv: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Reads up to and including `<eoh>`, returning the header text and any bytes read past it
+fn read_header<R: BufRead>(
+    reader: &mut R,
+    max_length: usize,
+) -> Result<(Option<String>, Vec<u8>), Error> {
+    let mut buffer = Vec::new();
+
+    if fill_buf(reader)?.starts_with(b"<") {
+        return Ok((None, buffer));
+    }
+
+    loop {
+        if reader.read_until(b'>', &mut buffer)? == 0 {
+            return Err(Error::NoData);
+        }
+        if buffer.len() > max_length {
+            return Err(Error::RecordTooLong(max_length));
+        }
+        if buffer.len() >= 5 && buffer[(buffer.len() - 5)..].eq_ignore_ascii_case(b"<eoh>") {
+            break;
+        }
+    }
+
+    let text =
+        String::from_utf8(buffer).map_err(|_| Error::OtherError("header is not valid utf-8"))?;
+    let consumed = {
+        let (_, rest) = Header::parse(&text)?;
+        text.len() - rest.len()
+    };
+
+    let rest = text.as_bytes()[consumed..].to_vec();
+    let mut text = text;
+    text.truncate(consumed);
+    Ok((Some(text), rest))
+}
+
+fn fill_buf<R: BufRead>(reader: &mut R) -> Result<&[u8], Error> {
+    loop {
+        match reader.fill_buf() {
+            Ok(_) => break,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(reader.fill_buf()?)
+}
+
+/// Finds the end of the first complete record in `buffer`, or `None` if more input is needed
+fn find_record_end(buffer: &[u8]) -> Result<Option<usize>, Error> {
+    let mut pos = 0;
+    loop {
+        while buffer.get(pos).is_some_and(u8::is_ascii_whitespace) {
+            pos += 1;
+        }
+        match buffer.get(pos) {
+            None => return Ok(None),
+            Some(b'<') => {}
+            Some(_) => {
+                return Err(Error::OtherError("could not find start of data specifier"));
+            }
+        }
+
+        let Some(close) = buffer[pos..].iter().position(|b| *b == b'>') else {
+            return Ok(None);
+        };
+        let specifier = std::str::from_utf8(&buffer[(pos + 1)..(pos + close)])
+            .map_err(|_| Error::OtherError("data specifier is not valid utf-8"))?;
+        pos += close + 1;
+
+        match parse_specifier(specifier)? {
+            Some(s) => {
+                pos += s.length;
+                if pos > buffer.len() {
+                    return Ok(None);
+                }
+            }
+            None if specifier.eq_ignore_ascii_case("eor") => return Ok(Some(pos)),
+            None => return Err(Error::OtherError("no colon in data specifier")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DeserializeRecordsStream<R, D> {
+    reader: R,
+    buffer: Vec<u8>,
+    /// Position of the start of `buffer` in the input
+    cursor: Cursor,
+    max_record_length: usize,
+    record: usize,
+    lenient: bool,
+    diagnostics: Vec<Diagnostic>,
+    done: bool,
+    _priv: std::marker::PhantomData<D>,
+}
+
+impl<R: BufRead, D> DeserializeRecordsStream<R, D> {
+    /// Skips malformed records instead of returning errors, see [`Self::diagnostics`]
+    pub fn lenient(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+
+    /// Errors for the records that were skipped in lenient mode
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn error_position(&self, offset: usize, field: Option<String>) -> Position {
+        let mut cursor = self.cursor;
+        cursor.advance(&self.buffer[..offset.min(self.buffer.len())]);
+        cursor.position(self.record, field)
+    }
+
+    fn consume(&mut self, length: usize) {
+        self.cursor.advance(&self.buffer[..length]);
+        self.buffer.drain(..length);
+    }
+
+    /// Reads more input into the buffer, returns `false` at the end of the input
+    fn read_more(&mut self) -> Result<bool, Error> {
+        let available = fill_buf(&mut self.reader)?;
+        if available.is_empty() {
+            return Ok(false);
+        }
+
+        let length = available.len();
+        self.buffer.extend_from_slice(available);
+        self.reader.consume(length);
+        Ok(true)
+    }
+
+    /// Reads until a whole record is buffered, returns its length
+    fn buffer_record(&mut self) -> Result<usize, Error> {
+        loop {
+            if let Some(end) = find_record_end(&self.buffer)? {
+                return Ok(end);
+            }
+
+            if self.buffer.len() > self.max_record_length {
+                return Err(Error::RecordTooLong(self.max_record_length));
+            }
+
+            if !self.read_more()? {
+                return if self.buffer.iter().all(u8::is_ascii_whitespace) {
+                    Err(Error::NoData)
+                } else {
+                    Err(Error::UnexpectedEndOfInput("while reading record"))
+                };
+            }
+        }
+    }
+
+    /// Drops input up to and including the next `<eor>`
+    fn skip_record(&mut self) -> Result<(), Error> {
+        loop {
+            if let Some(end) = find_eor_end(&self.buffer) {
+                self.consume(end);
+                return Ok(());
+            }
+
+            // keep enough to find an `<eor>` split across reads
+            self.consume(self.buffer.len().saturating_sub(4));
+            if !self.read_more()? {
+                self.consume(self.buffer.len());
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<R: BufRead, D: serde::de::DeserializeOwned> Iterator for DeserializeRecordsStream<R, D> {
+    type Item = Result<D, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let (error, offset, field) = match self.buffer_record() {
+                Ok(end) => {
+                    let result = match std::str::from_utf8(&self.buffer[..end]) {
+                        Ok(text) => {
+                            let mut rest = text;
+                            deserialize_record(&mut rest)
+                                .map_err(|e| (e.error, text.len() - rest.len(), e.field))
+                        }
+                        Err(e) => Err((
+                            Error::OtherError("record is not valid utf-8"),
+                            e.valid_up_to(),
+                            None,
+                        )),
+                    };
+
+                    match result {
+                        Ok(record) => {
+                            self.consume(end);
+                            self.record += 1;
+                            return Some(Ok(record));
+                        }
+                        Err((error, offset, field)) => {
+                            let position = self.error_position(offset, field);
+                            self.consume(end);
+                            self.record += 1;
+                            if !self.lenient {
+                                return Some(Err(error.at(position)));
+                            }
+                            self.diagnostics.push(Diagnostic { position, error });
+                            continue;
+                        }
+                    }
+                }
+                Err(Error::NoData) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    let start = self
+                        .buffer
+                        .iter()
+                        .take_while(|b| b.is_ascii_whitespace())
+                        .count();
+                    (e, start, None)
+                }
+            };
+
+            let position = self.error_position(offset, field);
+            self.record += 1;
+
+            if !self.lenient || matches!(error, Error::Io(_)) {
+                // the position in the input is unknown after an error
+                self.done = true;
+                return Some(Err(error.at(position)));
+            }
+
+            self.diagnostics.push(Diagnostic { position, error });
+            if let Err(e) = self.skip_record() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        None
+    }
+}
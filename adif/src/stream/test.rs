@@ -0,0 +1,116 @@
+use std::io::BufReader;
+
+use super::StreamReader;
+use crate::reader::{Error, Reader};
+
+const ADI: &str = "Test log
+<ADIF_VER:5>3.1.4 <PROGRAMID:4>test
+<EOH>
+<CALL:4>W1AW <NAME_INTL:10>Jürgen Ö <FREQ:6>14.074 <EOR>
+<CALL:6>JA1ABC <NAME_INTL:3>Aki <FREQ:3>7.0 <EOR>
+<CALL:5>DL1AB <COMMENT:5><EOR> <FREQ:4>3.55 <EOR>
+";
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Record {
+    call: String,
+    name_intl: Option<String>,
+    comment: Option<String>,
+    freq: f64,
+}
+
+fn read_with_capacity(capacity: usize) -> Vec<Record> {
+    let reader = StreamReader::new(BufReader::with_capacity(capacity, ADI.as_bytes())).unwrap();
+    assert_eq!(reader.header().unwrap().program_id, Some("test"));
+    reader.deserialize().collect::<Result<Vec<_>, _>>().unwrap()
+}
+
+#[test]
+fn matches_borrowed_reader() {
+    let borrowed: Vec<Record> = Reader::from_str(ADI)
+        .unwrap()
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(borrowed.len(), 3);
+    assert_eq!(borrowed[2].comment.as_deref(), Some("<EOR>"));
+
+    // small buffers split specifiers, values and multi-byte characters across reads
+    for capacity in [1, 2, 3, 7, 64, 8192] {
+        assert_eq!(read_with_capacity(capacity), borrowed);
+    }
+}
+
+#[test]
+fn no_header() {
+    let records: Vec<Record> = StreamReader::new("<CALL:4>W1AW <FREQ:1>7 <EOR>".as_bytes())
+        .unwrap()
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 1);
+}
+
+#[test]
+fn truncated_record() {
+    let mut records = StreamReader::new("<CALL:4>W1AW <FREQ:1>7 <EOR>\n<CALL:4>W1".as_bytes())
+        .unwrap()
+        .deserialize::<Record>();
+    assert!(records.next().unwrap().is_ok());
+
+    let err = records.next().unwrap().unwrap_err();
+    assert_eq!(err.position().unwrap().line, 2);
+    assert!(matches!(err.into_inner(), Error::UnexpectedEndOfInput(_)));
+    assert!(records.next().is_none());
+}
+
+#[test]
+fn record_too_long() {
+    let mut records = StreamReader::with_max_record_length(
+        BufReader::with_capacity(
+            4,
+            "<CALL:4>W1AW <COMMENT:20>aaaaaaaaaaaaaaaaaaaa <EOR>".as_bytes(),
+        ),
+        16,
+    )
+    .unwrap()
+    .deserialize::<Record>();
+    assert!(matches!(
+        records.next().map(|r| r.map_err(Error::into_inner)),
+        Some(Err(Error::RecordTooLong(16)))
+    ));
+}
+
+#[test]
+fn missing_eoh() {
+    assert!(matches!(
+        StreamReader::new("header without end <ADIF_VER:5>3.1.4".as_bytes()),
+        Err(Error::NoData)
+    ));
+}
+
+#[test]
+fn lenient() {
+    let input = "log\n<EOH>\n<CALL:4>W1AW <FREQ:1>7 <EOR>\n\
+        <CALL:4>K9ET <FREQ:1>x <EOR>\n\
+        garbage <CALL:4>KD9M <FREQ:1>7 <EOR>\n\
+        <CALL:6>JA1ABC <FREQ:1>7 <eor>\n";
+
+    for capacity in [1, 5, 8192] {
+        let mut records = StreamReader::new(BufReader::with_capacity(capacity, input.as_bytes()))
+            .unwrap()
+            .deserialize::<Record>()
+            .lenient();
+        let calls: Vec<_> = (&mut records).map(|r| r.unwrap().call).collect();
+        assert_eq!(calls, vec!["W1AW", "JA1ABC"]);
+
+        let diagnostics = records.take_diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].position.record, 1);
+        assert_eq!(diagnostics[0].position.line, 4);
+        assert_eq!(diagnostics[0].position.field.as_deref(), Some("FREQ"));
+        assert_eq!(diagnostics[1].position.record, 2);
+        assert_eq!(diagnostics[1].position.line, 5);
+    }
+}
@@ -0,0 +1,304 @@
+//! Round trips through the writers and readers, and inputs that must not make the readers panic
+
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adx,
+    data_type::DataType,
+    enumeration::{BANDS, MODES},
+    reader::Reader,
+    record::Record,
+    stream::StreamReader,
+    writer::Writer,
+    Band, GridSquare, Location, Mode,
+};
+
+/// Seed inputs for the `reader` fuzz target, each one a quirk seen in real exports
+const CORPUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/corpus/reader");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Contact {
+    call: String,
+    freq: f64,
+    qso_random: bool,
+    stx: Option<u32>,
+    band: Band,
+    mode: Mode,
+    lat: Option<Location>,
+    gridsquare: Option<GridSquare>,
+    comment_intl: Option<String>,
+}
+
+fn field_name() -> impl Strategy<Value = String> {
+    "[A-Za-z][A-Za-z0-9_]{0,15}".prop_filter("reserved name", |name| {
+        let name = name.to_ascii_uppercase();
+        !matches!(name.as_str(), "EOR" | "EOH" | "APP")
+            && !name.starts_with("USERDEF")
+            && !name.ends_with("_INTL")
+    })
+}
+
+fn typed_value() -> impl Strategy<Value = (Option<DataType>, String)> {
+    prop_oneof![
+        ".*".prop_map(|v| (None, v)),
+        "[YNyn]".prop_map(|v| (Some(DataType::Boolean), v)),
+        "-?[0-9]{1,6}(\\.[0-9]{1,4})?".prop_map(|v| (Some(DataType::Number), v)),
+        "(19|20)[0-9]{2}(0[1-9]|1[0-2])(0[1-9]|[12][0-9]|3[01])"
+            .prop_map(|v| (Some(DataType::Date), v)),
+        "([01][0-9]|2[0-3])[0-5][0-9]([0-5][0-9])?".prop_map(|v| (Some(DataType::Time), v)),
+        "[ -~]*".prop_map(|v| (Some(DataType::String), v)),
+        "[^\r\n]*".prop_map(|v| (Some(DataType::IntlString), v)),
+        "[ -~\r\n]*".prop_map(|v| (Some(DataType::MultilineString), v)),
+        ".*".prop_map(|v| (Some(DataType::IntlMultilineString), v)),
+        "[A-Z0-9]{0,8}".prop_map(|v| (Some(DataType::Enumeration), v)),
+        "[NS](0[0-8][0-9]) [0-5][0-9]\\.[0-9]{3}".prop_map(|v| (Some(DataType::Location), v)),
+    ]
+}
+
+fn record() -> impl Strategy<Value = Record> {
+    prop::collection::btree_map(field_name(), typed_value(), 0..12).prop_map(|fields| {
+        let mut record = Record::new();
+        let mut names = std::collections::HashSet::new();
+        for (name, (data_type, value)) in fields {
+            // names are unique ignoring case
+            if names.insert(name.to_ascii_uppercase()) {
+                record.insert(name, data_type, value);
+            }
+        }
+        record
+    })
+}
+
+fn contact() -> impl Strategy<Value = Contact> {
+    (
+        "[A-Z0-9/]{1,12}",
+        -1e9..1e9f64,
+        any::<bool>(),
+        any::<Option<u32>>(),
+        prop::sample::select(BANDS.iter().map(|b| b.name).collect::<Vec<_>>()),
+        prop::sample::select(MODES.iter().map(|m| m.0).collect::<Vec<_>>()),
+        prop::option::of(-90.0..=90.0f64),
+        prop::option::of("[A-R]{2}([0-9]{2}([a-x]{2})?)?"),
+        prop::option::of("\\PC*"),
+    )
+        .prop_map(
+            |(call, freq, qso_random, stx, band, mode, lat, gridsquare, comment_intl)| Contact {
+                call,
+                freq,
+                qso_random,
+                stx,
+                band: band.into(),
+                mode: mode.into(),
+                lat: lat.map(|l| Location::latitude(l).unwrap()),
+                gridsquare: gridsquare.map(|g| g.parse().unwrap()),
+                comment_intl,
+            },
+        )
+}
+
+/// Fragments of ADI, so that generated inputs get past the first `<`
+fn adi_input() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        prop_oneof![
+            Just("<".to_owned()),
+            Just(">".to_owned()),
+            Just(":".to_owned()),
+            Just("<EOR>".to_owned()),
+            Just("<eoh>".to_owned()),
+            "<[a-z_]{1,6}:[0-9]{1,2}(:[A-Za-z])?>",
+            "[0-9]{1,3}",
+            "\\PC{0,4}",
+            "[éü日本😀]{1,3}",
+        ],
+        0..40,
+    )
+    .prop_map(|parts| parts.concat())
+}
+
+fn write_adi<'r>(records: impl IntoIterator<Item = &'r Record>) -> String {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_header("generated", &()).unwrap();
+    for record in records {
+        writer.write_record(record).unwrap();
+    }
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+/// The records as they read back, names are written in upper case and ADX only has types
+/// for `APP_` fields
+fn as_written(records: &[Record], adx: bool) -> Vec<Record> {
+    records
+        .iter()
+        .map(|record| {
+            let mut written = Record::new();
+            for field in record {
+                let name = field.name.to_ascii_uppercase();
+                let is_app = name
+                    .strip_prefix("APP_")
+                    .and_then(|n| n.split_once('_'))
+                    .is_some_and(|(program, field)| !program.is_empty() && !field.is_empty());
+                let data_type = field.data_type.filter(|_| !adx || is_app);
+                written.insert(name, data_type, field.value.clone());
+            }
+            written
+        })
+        .collect()
+}
+
+/// Reads `input` every way there is, only checking that nothing panics
+fn read_all(input: &str) {
+    if let Ok(reader) = Reader::from_str(input) {
+        let _ = reader.header();
+        for record in reader.deserialize::<Record>().unwrap() {
+            let _ = record.map(|r| r.get_location("LAT"));
+        }
+        reader
+            .deserialize::<Contact>()
+            .unwrap()
+            .lenient()
+            .for_each(drop);
+    }
+
+    if let Ok(reader) = StreamReader::new(input.as_bytes()) {
+        let _ = reader.header();
+        reader.deserialize::<Record>().lenient().for_each(drop);
+    }
+
+    if let Ok(reader) = adx::Reader::from_str(input) {
+        reader.deserialize::<Record>().lenient().for_each(drop);
+    }
+}
+
+proptest! {
+    #[test]
+    fn adi_records(records in prop::collection::vec(record(), 0..8)) {
+        let output = write_adi(&records);
+
+        let read: Vec<Record> = Reader::from_str(&output)
+            .unwrap()
+            .deserialize()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let records = as_written(&records, false);
+        prop_assert_eq!(&read, &records);
+
+        let streamed: Vec<Record> = StreamReader::new(output.as_bytes())
+            .unwrap()
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        prop_assert_eq!(&streamed, &records);
+    }
+
+    #[test]
+    fn adx_records(records in prop::collection::vec(record(), 0..8)) {
+        let mut writer = adx::Writer::new(Vec::new());
+        for record in &records {
+            writer.write_record(record).unwrap();
+        }
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        let read: Vec<Record> = adx::Reader::from_str(&output)
+            .unwrap()
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        prop_assert_eq!(read, as_written(&records, true));
+    }
+
+    #[test]
+    fn contacts(contacts in prop::collection::vec(contact(), 1..8)) {
+        let mut writer = Writer::new(Vec::new());
+        for contact in &contacts {
+            writer.serialize(contact).unwrap();
+        }
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let read: Vec<Contact> = Reader::from_str(&output)
+            .unwrap()
+            .deserialize()
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        prop_assert_eq!(&read, &contacts);
+
+        let mut writer = adx::Writer::new(Vec::new());
+        for contact in &contacts {
+            writer.serialize(contact).unwrap();
+        }
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let read: Vec<Contact> = adx::Reader::from_str(&output)
+            .unwrap()
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        prop_assert_eq!(read, contacts);
+    }
+
+    #[test]
+    fn arbitrary_input(input in prop_oneof![adi_input(), any::<String>()]) {
+        read_all(&input);
+    }
+
+    #[test]
+    fn truncated_input(records in prop::collection::vec(record(), 1..4), cut in any::<prop::sample::Index>()) {
+        let output = write_adi(&records);
+        let cut = cut.index(output.len());
+        if let Some(input) = output.get(..cut) {
+            read_all(input);
+        }
+    }
+}
+
+fn corpus() -> Vec<(String, String)> {
+    let mut files: Vec<_> = std::fs::read_dir(CORPUS)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn quirks() {
+    let calls = |input: &str| -> (Vec<String>, usize) {
+        let reader = Reader::from_str(input).unwrap();
+        let mut records = reader.deserialize::<Record>().unwrap().lenient();
+        let calls = (&mut records)
+            .map(|r| r.unwrap().get("call").unwrap_or_default().to_owned())
+            .collect();
+        (calls, records.diagnostics().len())
+    };
+
+    let files = corpus();
+    assert!(!files.is_empty());
+    for (name, input) in &files {
+        read_all(input);
+
+        let (calls, skipped) = calls(input);
+        let expected = match name.as_str() {
+            "lowercase_tags.adi" => (vec!["W1AW", "K9ET"], 0),
+            "missing_header.adi" => (vec!["W1AW", "K9ET"], 0),
+            "trailing_garbage.adi" => (vec!["W1AW", "K9ET"], 1),
+            "multi_byte.adi" => (vec!["DL1ABC", "JA1XYZ", "SP5ŁÓ"], 0),
+            "split_character.adi" => (vec!["W1AW"], 1),
+            "crlf.adi" => (vec!["W1AW", "K9ET"], 0),
+            _ => continue,
+        };
+        assert_eq!(
+            (
+                calls.iter().map(String::as_str).collect::<Vec<_>>(),
+                skipped
+            ),
+            expected,
+            "{}",
+            name
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    catalogue::{self, Check},
+    data_type::DataType,
+    enumeration,
+    header::{Header, UserDefinedField},
+    reader::Error,
+    record::{Field, Record},
+};
+
+#[cfg(test)]
+mod test;
+
+/// Why a field does not conform to the specification
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Problem {
+    /// Not a field from the specification, an `APP_` field or a user defined field
+    UnknownField,
+    InvalidValue(DataType),
+    FailedCheck(Check),
+    /// A user defined field value outside the enumeration or range from the header
+    NotInUserDefinition,
+    /// `SUBMODE` does not belong to the `MODE` of the record
+    SubmodeMismatch(&'static str),
+    /// The frequency is not in the `BAND` of the record
+    FrequencyOutsideBand(&'static str),
+    /// The record could not be read at all, with why
+    Unreadable(String),
+}
+
+impl core::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownField => f.write_str("unknown field"),
+            Self::InvalidValue(t) => write!(f, "not a valid `{}`", t),
+            Self::FailedCheck(c) => write!(f, "not {}", c),
+            Self::NotInUserDefinition => f.write_str("not allowed by the header definition"),
+            Self::SubmodeMismatch(mode) => write!(f, "submode of `{}`, not of this mode", mode),
+            Self::FrequencyOutsideBand(band) => write!(f, "outside of the `{}` band", band),
+            Self::Unreadable(error) => write!(f, "unreadable, {}", error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// Index of the record in the file, starting at zero
+    pub record: usize,
+    pub field: String,
+    pub value: String,
+    pub problem: Problem,
+}
+
+impl core::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Problem::Unreadable(_) = self.problem {
+            return match self.field.as_str() {
+                "" => write!(f, "record {}: {}", self.record, self.problem),
+                field => write!(
+                    f,
+                    "record {} field `{}`: {}",
+                    self.record, field, self.problem
+                ),
+            };
+        }
+        write!(
+            f,
+            "record {} field `{}` value `{}`: {}",
+            self.record, self.field, self.value, self.problem
+        )
+    }
+}
+
+/// Checks records against the field catalogue and the user defined fields of a header
+#[derive(Debug, Clone, Default)]
+pub struct Validator<'h> {
+    user_defined_fields: Vec<UserDefinedField<'h>>,
+    allow_unknown: bool,
+}
+
+impl<'h> Validator<'h> {
+    pub fn new(header: Option<&Header<'h>>) -> Self {
+        Self {
+            user_defined_fields: header
+                .map(|h| h.user_defined_fields.clone())
+                .unwrap_or_default(),
+            allow_unknown: false,
+        }
+    }
+
+    /// Don't report fields that are not in the catalogue
+    pub fn allow_unknown(mut self) -> Self {
+        self.allow_unknown = true;
+        self
+    }
+
+    /// Returns every problem in `record`, with the field it was found in
+    pub fn check<'r>(&self, record: &'r Record) -> Vec<(&'r Field, Problem)> {
+        let mut problems = Vec::new();
+        for field in record {
+            if let Some(problem) = self.check_field(field) {
+                problems.push((field, problem));
+            }
+        }
+
+        if let (Some(mode), Some(submode)) = (record.field("MODE"), record.field("SUBMODE")) {
+            match enumeration::mode_for_submode(&submode.value) {
+                Some(m) if !m.eq_ignore_ascii_case(&mode.value) && !mode.value.is_empty() => {
+                    problems.push((submode, Problem::SubmodeMismatch(m)))
+                }
+                _ => {}
+            }
+        }
+
+        for (freq, band) in [("FREQ", "BAND"), ("FREQ_RX", "BAND_RX")] {
+            let (Some(freq), Some(band)) = (record.field(freq), record.get(band)) else {
+                continue;
+            };
+            let (Ok(mhz), Some(band)) = (freq.value.parse::<f64>(), enumeration::band(band)) else {
+                continue;
+            };
+            if !band.contains(mhz) {
+                problems.push((freq, Problem::FrequencyOutsideBand(band.name)));
+            }
+        }
+
+        problems
+    }
+
+    fn check_field(&self, field: &Field) -> Option<Problem> {
+        let value = field.value.as_str();
+
+        if let Some(info) = catalogue::field(&field.name) {
+            if !info.data_type.is_valid(value) {
+                return Some(Problem::InvalidValue(info.data_type));
+            }
+            return match info.check {
+                Some(check) if !check.is_valid(value) => Some(Problem::FailedCheck(check)),
+                _ => None,
+            };
+        }
+
+        if let Some(user_defined) = self
+            .user_defined_fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(&field.name))
+        {
+            return check_user_defined(user_defined, value);
+        }
+
+        let is_app = field.name.len() > 4 && field.name[..4].eq_ignore_ascii_case("app_");
+        (!is_app && !self.allow_unknown).then_some(Problem::UnknownField)
+    }
+}
+
+fn check_user_defined(field: &UserDefinedField, value: &str) -> Option<Problem> {
+    if value.is_empty() {
+        return None;
+    }
+    if let Some(data_type) = field.data_type {
+        if !data_type.is_valid(value) {
+            return Some(Problem::InvalidValue(data_type));
+        }
+    }
+
+    let allowed = match (&field.enumeration, field.range) {
+        (Some(values), _) => values.iter().any(|v| v.eq_ignore_ascii_case(value)),
+        (None, Some((min, max))) => value.parse::<f64>().is_ok_and(|v| (min..=max).contains(&v)),
+        (None, None) => true,
+    };
+    (!allowed).then_some(Problem::NotInUserDefinition)
+}
+
+/// Reads an ADI file and reports every field that does not conform to the specification
+pub fn validate(input: &str) -> Result<Vec<Issue>, Error> {
+    let reader = crate::reader::Reader::from_str(input)?;
+    let validator = Validator::new(reader.header());
+    Ok(validate_records(&validator, reader.deserialize()?))
+}
+
+/// Reads an ADX file and reports every field that does not conform to the specification
+pub fn validate_adx(input: &str) -> Result<Vec<Issue>, Error> {
+    let reader = crate::adx::Reader::from_str(input)?;
+    // the header borrows from the reader, which is consumed by reading the records
+    let header = reader.header_adi().map(str::to_owned);
+    let header = match header.as_deref() {
+        Some(h) => Some(Header::parse(h)?.0),
+        None => None,
+    };
+    let validator = Validator::new(header.as_ref());
+    Ok(validate_records(&validator, reader.deserialize()))
+}
+
+/// Checks every record, a record that can't be read is an issue of its own and the rest are
+/// still checked
+fn validate_records(
+    validator: &Validator,
+    records: impl Iterator<Item = Result<Record, Error>>,
+) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (i, record) in records.enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                issues.push(Issue {
+                    record: i,
+                    field: e
+                        .position()
+                        .and_then(|p| p.field.clone())
+                        .unwrap_or_default(),
+                    value: String::new(),
+                    problem: Problem::Unreadable(e.kind().to_string()),
+                });
+                continue;
+            }
+        };
+        issues.extend(
+            validator
+                .check(&record)
+                .into_iter()
+                .map(|(field, problem)| Issue {
+                    record: i,
+                    field: field.name.clone(),
+                    value: field.value.clone(),
+                    problem,
+                }),
+        );
+    }
+    issues
+}
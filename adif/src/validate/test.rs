@@ -0,0 +1,139 @@
+use super::{validate, validate_adx, Problem, Validator};
+use crate::{
+    catalogue::{self, Check},
+    data_type::DataType,
+    enumeration::{self, Enumeration},
+    Record,
+};
+
+#[test]
+fn catalogue() {
+    let band = catalogue::field("band").unwrap();
+    assert_eq!(band.data_type, DataType::Enumeration);
+    assert_eq!(band.check, Some(Check::Enumeration(Enumeration::Band)));
+    assert!(catalogue::field("SECTION").is_none());
+
+    assert!(Enumeration::Band.contains("20M"));
+    assert!(!Enumeration::Band.contains("21m"));
+    assert!(Enumeration::Mode.contains("ft8"));
+    assert!(Enumeration::Submode.contains("USB"));
+    assert!(Enumeration::ArrlSection.contains("wwa"));
+    assert!(Enumeration::Continent.contains("OC"));
+    assert!(!Enumeration::Continent.contains("XX"));
+
+    assert_eq!(enumeration::band_for_frequency(14.074).unwrap().name, "20m");
+    assert_eq!(enumeration::mode_for_submode("FT4"), Some("MFSK"));
+    assert_eq!(enumeration::submodes("SSB"), Some(&["LSB", "USB"][..]));
+
+    assert!(Check::GridSquare.is_valid("CN87ux"));
+    assert!(!Check::GridSquare.is_valid("ZZ87"));
+    assert!(Check::Range(1.0, 40.0).is_valid("5"));
+    assert!(!Check::Range(1.0, 40.0).is_valid("41"));
+}
+
+#[test]
+fn record() {
+    let mut record = Record::new();
+    record.insert("CALL", None, "W1AW");
+    record.insert("BAND", None, "21m");
+    record.insert("MODE", None, "SSB");
+    record.insert("SUBMODE", None, "FT4");
+    record.insert("CONT", None, "NA");
+    record.insert("CQZ", None, "41");
+    record.insert("QSO_DATE", None, "2023");
+    record.insert("SECTION", None, "WWA");
+    record.insert("APP_N1MM_ID", None, "abc");
+
+    let problems: Vec<_> = Validator::new(None)
+        .check(&record)
+        .into_iter()
+        .map(|(f, p)| (f.name.as_str(), p))
+        .collect();
+    assert_eq!(
+        problems,
+        vec![
+            (
+                "BAND",
+                Problem::FailedCheck(Check::Enumeration(Enumeration::Band))
+            ),
+            ("CQZ", Problem::FailedCheck(Check::Range(1.0, 40.0))),
+            ("QSO_DATE", Problem::InvalidValue(DataType::Date)),
+            ("SECTION", Problem::UnknownField),
+            ("SUBMODE", Problem::SubmodeMismatch("MFSK")),
+        ]
+    );
+
+    assert_eq!(Validator::new(None).allow_unknown().check(&record).len(), 4);
+}
+
+#[test]
+fn file() {
+    let input = "header
+<USERDEF1:10:N>EPC,{5:20}
+<USERDEF2:19:E>SWEATERSIZE,{S,M,L}
+<EOH>
+<CALL:4>W1AW <BAND:3>20m <FREQ:6>14.074 <EPC:2>10 <SWEATERSIZE:1>M <EOR>
+<CALL:4>K9ET <BAND:3>40m <FREQ:6>14.074 <EPC:2>30 <SWEATERSIZE:2>XL <EOR>
+<CALL:4>KD9M <FREQ_RX:3>7.1 <BAND_RX:3>40m <CONT:2>na <EOR>
+";
+
+    let issues = validate(input).unwrap();
+    assert_eq!(
+        issues
+            .iter()
+            .map(|i| (i.record, i.field.as_str(), &i.problem))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, "EPC", &Problem::NotInUserDefinition),
+            (1, "SWEATERSIZE", &Problem::NotInUserDefinition),
+            (1, "FREQ", &Problem::FrequencyOutsideBand("40m")),
+        ]
+    );
+    assert_eq!(
+        issues[2].to_string(),
+        "record 1 field `FREQ` value `14.074`: outside of the `40m` band"
+    );
+}
+
+#[test]
+fn adx_file() {
+    let input = r#"<ADX><HEADER>
+<USERDEF FIELDID="1" TYPE="N" RANGE="{5:20}">EPC</USERDEF>
+</HEADER><RECORDS>
+<RECORD><CALL>W1AW</CALL><EPC>4</EPC><MODE>CW</MODE><ARRL_SECT>XX</ARRL_SECT></RECORD>
+</RECORDS></ADX>"#;
+
+    let issues = validate_adx(input).unwrap();
+    assert_eq!(
+        issues
+            .iter()
+            .map(|i| (i.field.as_str(), &i.problem))
+            .collect::<Vec<_>>(),
+        vec![
+            ("EPC", &Problem::NotInUserDefinition),
+            (
+                "ARRL_SECT",
+                &Problem::FailedCheck(Check::Enumeration(Enumeration::ArrlSection))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unreadable_record() {
+    let input = "<EOH>
+<CALL:4>W1AW <FREQ 6>14.074 <EOR>
+<CALL:4>K9ET <BAND:3>40m <FREQ:6>14.074 <EOR>
+";
+
+    let issues = validate(input).unwrap();
+    assert_eq!(
+        issues
+            .iter()
+            .map(|i| (i.record, i.field.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(0, ""), (1, "FREQ")]
+    );
+    assert!(matches!(issues[0].problem, Problem::Unreadable(_)));
+    assert!(issues[0].to_string().starts_with("record 0: unreadable, "));
+}
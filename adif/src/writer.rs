@@ -0,0 +1,569 @@
+use serde::ser::{self, Serialize};
+
+use crate::record::Record;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug)]
+pub struct Writer<W> {
+    writer: W,
+}
+
+impl<W: std::io::Write> Writer<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes the header block, the `preamble` text is free form but must not start with `<`
+    pub fn write_header<H: Serialize>(&mut self, preamble: &str, header: &H) -> Result<(), Error> {
+        if preamble.trim_start().is_empty() || preamble.starts_with('<') {
+            return Err(Error::InvalidPreamble);
+        }
+
+        self.writer.write_all(preamble.as_bytes())?;
+        if !preamble.ends_with('\n') {
+            self.writer.write_all(b"\n")?;
+        }
+
+        serialize_fields(header, &mut self.writer)?;
+        self.writer.write_all(b"<EOH>\n")?;
+        Ok(())
+    }
+
+    pub fn serialize<S: Serialize>(&mut self, record: &S) -> Result<(), Error> {
+        serialize_fields(record, &mut self.writer)?;
+        self.writer.write_all(b"<EOR>\n")?;
+        Ok(())
+    }
+
+    /// Writes a [`Record`], keeping the type indicators of its fields
+    pub fn write_record(&mut self, record: &Record) -> Result<(), Error> {
+        for field in record {
+            self.writer.write_field(
+                &field.name,
+                field.data_type.map(|t| t.indicator()),
+                &field.value,
+            )?;
+        }
+        self.writer.write_all(b"<EOR>\n")?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Serializes a single record, including the trailing `<EOR>`
+pub fn to_string<S: Serialize>(record: &S) -> Result<String, Error> {
+    let mut writer = Writer::new(Vec::new());
+    writer.serialize(record)?;
+    Ok(String::from_utf8(writer.into_inner()).expect("serializer only writes str data"))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("io error: `{0}`")]
+    Io(#[from] std::io::Error),
+
+    #[error("unsupported type, `{0}` cannot be written")]
+    UnsupportedType(&'static str),
+
+    #[error("field name `{0}` is not valid")]
+    InvalidFieldName(String),
+
+    #[error("header preamble must not be empty or start with `<`")]
+    InvalidPreamble,
+
+    #[error("the header must be written before any records")]
+    HeaderAfterRecords,
+
+    #[error("`{0}`")]
+    Custom(String),
+}
+
+impl ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: core::fmt::Display,
+    {
+        Self::Custom(format!("{}", msg))
+    }
+}
+
+/// Where the record serializer sends each field, so ADI and ADX can share it
+pub(crate) trait FieldWriter {
+    fn write_field(
+        &mut self,
+        name: &str,
+        data_type: Option<char>,
+        value: &str,
+    ) -> Result<(), Error>;
+}
+
+impl<W: std::io::Write> FieldWriter for W {
+    fn write_field(
+        &mut self,
+        name: &str,
+        data_type: Option<char>,
+        value: &str,
+    ) -> Result<(), Error> {
+        check_field_name(name)?;
+
+        match data_type {
+            Some(t) => write!(
+                self,
+                "<{}:{}:{}>{} ",
+                name.to_ascii_uppercase(),
+                value.len(),
+                t,
+                value
+            )?,
+            None => write!(
+                self,
+                "<{}:{}>{} ",
+                name.to_ascii_uppercase(),
+                value.len(),
+                value
+            )?,
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn check_field_name(name: &str) -> Result<(), Error> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_graphic() && !matches!(c, ',' | ':' | '<' | '>' | '{' | '}'))
+    {
+        return Err(Error::InvalidFieldName(name.to_owned()));
+    }
+    Ok(())
+}
+
+/// Serializes a struct or map as one record, without the end of record marker
+pub(crate) fn serialize_fields<S, F>(record: &S, writer: &mut F) -> Result<(), Error>
+where
+    S: ?Sized + Serialize,
+    F: FieldWriter,
+{
+    record.serialize(&mut RecordSerializer { writer })
+}
+
+/// Serializes the top level struct or map of a record, each entry becomes one field
+struct RecordSerializer<'w, W> {
+    writer: &'w mut W,
+}
+
+impl<'a, 'w, W: FieldWriter> ser::Serializer for &'a mut RecordSerializer<'w, W> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<(), Error>;
+    type SerializeTuple = ser::Impossible<(), Error>;
+    type SerializeTupleStruct = ser::Impossible<(), Error>;
+    type SerializeTupleVariant = ser::Impossible<(), Error>;
+    type SerializeMap = MapSerializer<'a, 'w, W>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = ser::Impossible<(), Error>;
+
+    fn serialize_bool(self, _v: bool) -> Result<(), Error> {
+        Err(Error::UnsupportedType("bool"))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<(), Error> {
+        Err(Error::UnsupportedType("i8"))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<(), Error> {
+        Err(Error::UnsupportedType("i16"))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<(), Error> {
+        Err(Error::UnsupportedType("i32"))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<(), Error> {
+        Err(Error::UnsupportedType("i64"))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<(), Error> {
+        Err(Error::UnsupportedType("u8"))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<(), Error> {
+        Err(Error::UnsupportedType("u16"))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<(), Error> {
+        Err(Error::UnsupportedType("u32"))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<(), Error> {
+        Err(Error::UnsupportedType("u64"))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), Error> {
+        Err(Error::UnsupportedType("f32"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), Error> {
+        Err(Error::UnsupportedType("f64"))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), Error> {
+        Err(Error::UnsupportedType("char"))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<(), Error> {
+        Err(Error::UnsupportedType("str"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        Err(Error::UnsupportedType("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Err(Error::UnsupportedType("none"))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        Err(Error::UnsupportedType("unit variant"))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType("newtype variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::UnsupportedType("seq"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::UnsupportedType("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::UnsupportedType("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::UnsupportedType("tuple variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(MapSerializer {
+            record: self,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::UnsupportedType("struct variant"))
+    }
+}
+
+impl<W: FieldWriter> ser::SerializeStruct for &mut RecordSerializer<'_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.writer.write_field(key, None, &value)?;
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+struct MapSerializer<'a, 'w, W> {
+    record: &'a mut RecordSerializer<'w, W>,
+    key: Option<String>,
+}
+
+impl<W: FieldWriter> ser::SerializeMap for MapSerializer<'_, '_, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        match key.serialize(ValueSerializer)? {
+            Some(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            None => Err(Error::UnsupportedType("empty key")),
+        }
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let Some(key) = self.key.take() else {
+            return Err(Error::Custom("value serialized before key".to_owned()));
+        };
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.record.writer.write_field(&key, None, &value)?;
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializes a single field value to its text, `None` means the field should be left out
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<Option<String>, Error>;
+    type SerializeTuple = ser::Impossible<Option<String>, Error>;
+    type SerializeTupleStruct = ser::Impossible<Option<String>, Error>;
+    type SerializeTupleVariant = ser::Impossible<Option<String>, Error>;
+    type SerializeMap = ser::Impossible<Option<String>, Error>;
+    type SerializeStruct = ser::Impossible<Option<String>, Error>;
+    type SerializeStructVariant = ser::Impossible<Option<String>, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+        Ok(Some(if v { "Y" } else { "N" }.to_owned()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Error> {
+        if v.is_finite() {
+            Ok(Some(v.to_string()))
+        } else {
+            Err(Error::UnsupportedType("non-finite f32"))
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Error> {
+        if v.is_finite() {
+            Ok(Some(v.to_string()))
+        } else {
+            Err(Error::UnsupportedType("non-finite f64"))
+        }
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+        Ok(Some(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Error> {
+        match core::str::from_utf8(v) {
+            Ok(s) => Ok(Some(s.to_owned())),
+            Err(_) => Err(Error::UnsupportedType("non utf-8 bytes")),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Error> {
+        Ok(Some(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType("newtype variant"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::UnsupportedType("seq"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::UnsupportedType("tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::UnsupportedType("tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::UnsupportedType("tuple variant"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::UnsupportedType("map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::UnsupportedType("struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::UnsupportedType("struct variant"))
+    }
+}
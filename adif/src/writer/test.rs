@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use super::{to_string, Error, Writer};
+use crate::reader::Reader;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record<'s> {
+    call: &'s str,
+    freq: f64,
+    cqz: u8,
+    operator: Option<&'s str>,
+    #[serde(rename = "app_n1mm_isrunqso")]
+    is_run: bool,
+}
+
+#[derive(Serialize)]
+struct Header<'s> {
+    adif_ver: &'s str,
+    programid: &'s str,
+}
+
+#[test]
+fn record() {
+    let record = Record {
+        call: "W1AW",
+        freq: 14.074,
+        cqz: 5,
+        operator: None,
+        is_run: true,
+    };
+    assert_eq!(
+        to_string(&record).unwrap(),
+        "<CALL:4>W1AW <FREQ:6>14.074 <CQZ:1>5 <APP_N1MM_ISRUNQSO:1>Y <EOR>\n"
+    );
+}
+
+#[test]
+fn map() {
+    let record: std::collections::BTreeMap<&str, &str> =
+        [("call", "W1AW"), ("mode", "CW")].into_iter().collect();
+    assert_eq!(
+        to_string(&record).unwrap(),
+        "<CALL:4>W1AW <MODE:2>CW <EOR>\n"
+    );
+}
+
+#[test]
+fn invalid_field_name() {
+    let record: std::collections::BTreeMap<&str, &str> = [("ca:ll", "W1AW")].into_iter().collect();
+    assert!(matches!(
+        to_string(&record),
+        Err(Error::InvalidFieldName(_))
+    ));
+}
+
+#[test]
+fn invalid_preamble() {
+    let mut writer = Writer::new(Vec::new());
+    let header = Header {
+        adif_ver: "3.1.4",
+        programid: "test",
+    };
+    assert!(matches!(
+        writer.write_header("", &header),
+        Err(Error::InvalidPreamble)
+    ));
+    assert!(matches!(
+        writer.write_header("<adif_ver:1>3", &header),
+        Err(Error::InvalidPreamble)
+    ));
+}
+
+#[test]
+fn round_trip() {
+    let records = [
+        Record {
+            call: "W1AW",
+            freq: 14.074,
+            cqz: 5,
+            operator: Some("K9ETS"),
+            is_run: true,
+        },
+        Record {
+            call: "JA1ABC",
+            freq: 7.0,
+            cqz: 25,
+            operator: None,
+            is_run: false,
+        },
+    ];
+
+    let mut writer = Writer::new(Vec::new());
+    writer.write_header("Test export", &()).unwrap();
+    for record in &records {
+        writer.serialize(record).unwrap();
+    }
+    let text = String::from_utf8(writer.into_inner()).unwrap();
+
+    let reader = Reader::from_str(&text).unwrap();
+    assert!(reader.header().unwrap().preamble.starts_with("Test export"));
+
+    let read: Vec<Record> = reader
+        .deserialize()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, records);
+}
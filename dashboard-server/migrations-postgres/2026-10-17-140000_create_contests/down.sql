@@ -0,0 +1,3 @@
+DROP INDEX contacts_contest_id;
+ALTER TABLE contacts DROP COLUMN contest_id;
+DROP TABLE contests;
@@ -0,0 +1,14 @@
+ALTER TABLE contacts ADD COLUMN grid_square VARCHAR;
+ALTER TABLE contacts ADD COLUMN sent_number INTEGER;
+ALTER TABLE contacts ADD COLUMN recv_number INTEGER;
+ALTER TABLE contacts ADD COLUMN recv_name VARCHAR;
+ALTER TABLE contacts ADD COLUMN recv_power VARCHAR;
+ALTER TABLE contacts ADD COLUMN recv_qth VARCHAR;
+ALTER TABLE contacts ADD COLUMN prec VARCHAR;
+ALTER TABLE contacts ADD COLUMN ck INTEGER;
+ALTER TABLE contacts ADD COLUMN comment VARCHAR;
+ALTER TABLE contacts ADD COLUMN continent VARCHAR;
+ALTER TABLE contacts ADD COLUMN country_prefix VARCHAR;
+ALTER TABLE contacts ADD COLUMN radio_number INTEGER;
+ALTER TABLE contacts ADD COLUMN station_name VARCHAR;
+ALTER TABLE contacts ADD COLUMN net_bios_name VARCHAR;
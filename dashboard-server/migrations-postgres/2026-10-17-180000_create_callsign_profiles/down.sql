@@ -0,0 +1 @@
+DROP TABLE callsign_profiles;
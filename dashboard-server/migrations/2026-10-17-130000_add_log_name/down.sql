@@ -0,0 +1 @@
+ALTER TABLE contacts DROP COLUMN log_name;
@@ -0,0 +1,31 @@
+CREATE TABLE contests (
+  id INTEGER PRIMARY KEY NOT NULL,
+
+  -- N1MM's contestnr and contestname, contestnr is 0 when only the name is known
+  contest_number INTEGER NOT NULL,
+  contest_name VARCHAR NOT NULL,
+  db_name VARCHAR,
+
+  first_seen TIMESTAMP NOT NULL,
+  last_seen TIMESTAMP NOT NULL,
+
+  UNIQUE (contest_number, contest_name)
+);
+
+ALTER TABLE contacts ADD COLUMN contest_id INTEGER REFERENCES contests (id);
+
+-- live contacts so far are grouped by name
+INSERT INTO contests (contest_number, contest_name, first_seen, last_seen)
+SELECT 0, COALESCE(contest_name, ''), MIN(timestamp), MAX(timestamp)
+FROM contacts
+WHERE log_name IS NULL
+GROUP BY COALESCE(contest_name, '');
+
+UPDATE contacts
+SET contest_id = (
+  SELECT id FROM contests
+  WHERE contest_number = 0 AND contest_name = COALESCE(contacts.contest_name, '')
+)
+WHERE log_name IS NULL;
+
+CREATE INDEX contacts_contest_id ON contacts (contest_id);
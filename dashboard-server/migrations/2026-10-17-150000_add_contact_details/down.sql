@@ -0,0 +1,14 @@
+ALTER TABLE contacts DROP COLUMN net_bios_name;
+ALTER TABLE contacts DROP COLUMN station_name;
+ALTER TABLE contacts DROP COLUMN radio_number;
+ALTER TABLE contacts DROP COLUMN country_prefix;
+ALTER TABLE contacts DROP COLUMN continent;
+ALTER TABLE contacts DROP COLUMN comment;
+ALTER TABLE contacts DROP COLUMN ck;
+ALTER TABLE contacts DROP COLUMN prec;
+ALTER TABLE contacts DROP COLUMN recv_qth;
+ALTER TABLE contacts DROP COLUMN recv_power;
+ALTER TABLE contacts DROP COLUMN recv_name;
+ALTER TABLE contacts DROP COLUMN recv_number;
+ALTER TABLE contacts DROP COLUMN sent_number;
+ALTER TABLE contacts DROP COLUMN grid_square;
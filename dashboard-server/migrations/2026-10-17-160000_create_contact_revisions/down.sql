@@ -0,0 +1,4 @@
+DROP INDEX contact_revisions_contact_id;
+DROP TABLE contact_revisions;
+
+ALTER TABLE contacts DROP COLUMN deleted_at;
@@ -0,0 +1,20 @@
+-- deleted contacts are kept so they can be restored
+ALTER TABLE contacts ADD COLUMN deleted_at TIMESTAMP;
+
+CREATE TABLE contact_revisions (
+  id INTEGER PRIMARY KEY NOT NULL,
+  contact_id VARCHAR NOT NULL,
+
+  -- Insert, Replace, Delete or Restore
+  action VARCHAR NOT NULL,
+  recorded_at TIMESTAMP NOT NULL,
+
+  -- the N1MM station and packet, for changes received over UDP
+  station_name VARCHAR,
+  packet TEXT,
+
+  -- the contact as JSON, as it was before a delete and after anything else
+  snapshot TEXT NOT NULL
+);
+
+CREATE INDEX contact_revisions_contact_id ON contact_revisions (contact_id);
@@ -0,0 +1,13 @@
+CREATE TABLE callsign_locations (
+  -- see location_cache::normalize
+  callsign VARCHAR PRIMARY KEY NOT NULL,
+
+  -- NoLocation when the lookup found nothing, so it isn't repeated until it expires
+  location_source VARCHAR NOT NULL,
+  latitude FLOAT,
+  longitude FLOAT,
+
+  fetched_at TIMESTAMP NOT NULL,
+  -- seconds
+  ttl INTEGER NOT NULL
+);
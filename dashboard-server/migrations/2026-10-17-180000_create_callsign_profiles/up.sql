@@ -0,0 +1,20 @@
+CREATE TABLE callsign_profiles (
+  -- see location_cache::normalize
+  callsign VARCHAR PRIMARY KEY NOT NULL,
+
+  name VARCHAR,
+  nick VARCHAR,
+  qth VARCHAR,
+  country VARCHAR,
+  grid VARCHAR,
+  -- ADIF entity number
+  dxcc INTEGER,
+  itu_zone INTEGER,
+  cq_zone INTEGER,
+  continent VARCHAR,
+  -- NULL when HamQTH doesn't know
+  lotw BOOLEAN,
+  eqsl BOOLEAN,
+
+  fetched_at TIMESTAMP NOT NULL
+);
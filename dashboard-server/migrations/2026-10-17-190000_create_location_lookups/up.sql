@@ -0,0 +1,10 @@
+-- contacts located by prefix because their lookup failed, tried again until it works
+CREATE TABLE location_lookups (
+  contact_id VARCHAR PRIMARY KEY NOT NULL,
+  attempts INTEGER NOT NULL,
+  queued_at TIMESTAMP NOT NULL,
+  next_run TIMESTAMP NOT NULL,
+  last_error VARCHAR
+);
+
+CREATE INDEX location_lookups_next_run ON location_lookups (next_run);
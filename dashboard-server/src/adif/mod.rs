@@ -0,0 +1,342 @@
+use crate::{
+    contact_data::{ContactData, LocationSource},
+    rst,
+};
+
+#[cfg(test)]
+mod test;
+
+pub fn read_adif(
+    adif: &str,
+) -> anyhow::Result<adif::reader::DeserializeRecordsIter<N1MMAdifRecord>> {
+    let adif = adif::reader::Reader::from_str(adif)?;
+    if let Some(header) = adif.header() {
+        print_header(header);
+    }
+
+    Ok(adif.deserialize()?)
+}
+
+pub fn read_adif_stream<R: std::io::BufRead>(
+    reader: R,
+) -> anyhow::Result<adif::stream::DeserializeRecordsStream<R, N1MMAdifRecord>> {
+    let adif = adif::stream::StreamReader::new(reader)?;
+    if let Some(header) = adif.header() {
+        print_header(&header);
+    }
+
+    Ok(adif.deserialize())
+}
+
+pub fn read_adx(
+    adx: &str,
+) -> anyhow::Result<adif::adx::DeserializeRecordsIter<'_, N1MMAdifRecord>> {
+    let adx = adif::adx::Reader::from_str(adx)?;
+    if let Some(header) = adx.header() {
+        print_header(&header);
+    }
+
+    Ok(adx.deserialize())
+}
+
+/// Every field in an ADI or ADX file that does not conform to the ADIF specification
+pub fn validate_file(path: &std::path::Path) -> anyhow::Result<Vec<adif::validate::Issue>> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(match path.extension() {
+        Some(e) if e.eq_ignore_ascii_case("adx") => adif::validate::validate_adx(&text)?,
+        _ => adif::validate::validate(&text)?,
+    })
+}
+
+fn print_header(header: &adif::header::Header) {
+    println!(
+        "ADIF version {} from {} {}",
+        header.adif_ver.unwrap_or("unknown"),
+        header.program_id.unwrap_or("unknown program"),
+        header.program_version.unwrap_or_default(),
+    );
+}
+
+pub fn write_adif<W: std::io::Write>(writer: W, contacts: &[ContactData]) -> anyhow::Result<()> {
+    let mut writer = adif::writer::Writer::new(writer);
+    writer.write_header(
+        "ADIF export from dashboard-server",
+        &ExportHeader::default(),
+    )?;
+    for contact in contacts {
+        writer.serialize(&ExportRecord::from(contact))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_adx<W: std::io::Write>(writer: W, contacts: &[ContactData]) -> anyhow::Result<()> {
+    let mut writer = adif::adx::Writer::new(writer);
+    writer.write_header(&ExportHeader::default())?;
+    for contact in contacts {
+        writer.serialize(&ExportRecord::from(contact))?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+#[derive(serde::Serialize, Debug)]
+struct ExportHeader {
+    adif_ver: &'static str,
+    programid: &'static str,
+    programversion: &'static str,
+}
+
+impl Default for ExportHeader {
+    fn default() -> Self {
+        Self {
+            adif_ver: "3.1.4",
+            programid: "dashboard-server",
+            programversion: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct N1MMAdifRecord {
+    #[serde(rename = "call")]
+    pub recv_callsign: String,
+
+    #[serde(with = "adif_date")]
+    pub qso_date: time::Date,
+
+    #[serde(with = "adif_time")]
+    pub time_on: time::Time,
+
+    #[serde(with = "adif_time")]
+    pub time_off: time::Time,
+
+    #[serde(rename = "freq")]
+    pub freq_tx: f64,
+    pub freq_rx: f64,
+
+    pub section: Option<String>,
+    pub band: adif::Band,
+    #[serde(rename = "station_callsign")]
+    pub sent_callsign: String,
+    #[serde(rename = "contest_id")]
+    pub contest_name: Option<String>,
+    pub mode: adif::Mode,
+
+    #[serde(rename = "rst_sent")]
+    pub sent_signal_report: rst::RST,
+    #[serde(rename = "rst_rcvd")]
+    pub recv_signal_report: rst::RST,
+
+    #[serde(rename = "cqz")]
+    /// CQ Zone of contacted station
+    pub cq_zone: u8,
+
+    #[serde(rename = "pfx")]
+    /// WPX prefix of contacted station
+    pub prefix_wpx: Option<String>,
+
+    #[serde(rename = "stx")]
+    /// Transmitted serial number
+    pub serial_number_tx: u32,
+    #[serde(rename = "srx", default, deserialize_with = "empty_as_none")]
+    /// Received serial number
+    pub serial_number_rx: Option<u32>,
+
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub name: Option<String>,
+    #[serde(rename = "rx_pwr", default, deserialize_with = "empty_as_none")]
+    pub rx_power: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub qth: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub precedence: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub check: Option<String>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub comment: Option<String>,
+
+    pub operator: Option<String>,
+
+    #[serde(rename = "app_n1mm_isrunqso")]
+    pub n1mm_is_run_qso: bool,
+    #[serde(rename = "app_n1mm_claimedqso")]
+    pub n1mm_is_claimed_qso: bool,
+    #[serde(rename = "app_n1mm_points")]
+    pub n1mm_points: i32,
+
+    #[serde(rename = "app_n1mm_mult1")]
+    pub n1mm_is_mult_1: bool,
+    #[serde(rename = "app_n1mm_mult2")]
+    pub n1mm_is_mult_2: bool,
+    #[serde(rename = "app_n1mm_mult3")]
+    pub n1mm_is_mult_3: bool,
+
+    #[serde(rename = "app_n1mm_exchange1")]
+    pub n1mm_exchange1: Option<String>,
+    #[serde(rename = "app_n1mm_misctext")]
+    pub n1mm_miscellaneous_text: Option<String>,
+    #[serde(rename = "app_n1mm_continent")]
+    pub n1mm_continent: adif::Continent,
+
+    #[serde(rename = "app_n1mm_radio_nr")]
+    pub n1mm_radio_number: i32,
+    #[serde(rename = "app_n1mm_netbiosname")]
+    pub n1mm_netbios_name: String,
+
+    #[serde(rename = "app_n1mm_id")]
+    pub n1mm_id: String,
+
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub lat: Option<adif::Location>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub lon: Option<adif::Location>,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    pub gridsquare: Option<adif::GridSquare>,
+}
+
+impl N1MMAdifRecord {
+    /// Latitude and longitude from the record, preferring `LAT`/`LON` over `GRIDSQUARE`
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        match (&self.lat, &self.lon) {
+            (Some(lat), Some(lon)) => Some((lat.to_decimal(), lon.to_decimal())),
+            _ => self.gridsquare.as_ref().map(adif::GridSquare::center),
+        }
+    }
+}
+
+/// N1MM writes fields it has no value for as empty
+fn empty_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+    value
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(serde::Serialize, Debug)]
+struct ExportRecord<'s> {
+    call: &'s str,
+
+    #[serde(with = "adif_date")]
+    qso_date: time::Date,
+    #[serde(with = "adif_time")]
+    time_on: time::Time,
+    #[serde(with = "adif_time")]
+    time_off: time::Time,
+
+    freq: f64,
+    freq_rx: f64,
+    band: Option<&'s adif::Band>,
+
+    section: Option<&'s str>,
+    station_callsign: &'s str,
+    contest_id: Option<&'s str>,
+    mode: &'s str,
+
+    rst_sent: &'s rst::RST,
+    rst_rcvd: &'s rst::RST,
+
+    cqz: i16,
+    pfx: Option<&'s str>,
+    operator: Option<&'s str>,
+
+    app_n1mm_isrunqso: bool,
+    app_n1mm_claimedqso: bool,
+    app_n1mm_points: i32,
+    app_n1mm_mult1: bool,
+    app_n1mm_mult2: bool,
+    app_n1mm_mult3: bool,
+    app_n1mm_exchange1: Option<&'s str>,
+    app_n1mm_continent: Option<&'s str>,
+    app_n1mm_id: Option<&'s str>,
+
+    lat: Option<adif::Location>,
+    lon: Option<adif::Location>,
+    gridsquare: Option<&'s str>,
+
+    stx: Option<i32>,
+    srx: Option<i32>,
+    name: Option<&'s str>,
+    rx_pwr: Option<&'s str>,
+    qth: Option<&'s str>,
+    precedence: Option<&'s str>,
+    check: Option<String>,
+    comment: Option<&'s str>,
+    cont: Option<&'s str>,
+
+    app_n1mm_radio_nr: Option<i32>,
+    app_n1mm_netbiosname: Option<&'s str>,
+}
+
+impl<'s> From<&'s ContactData> for ExportRecord<'s> {
+    fn from(value: &'s ContactData) -> Self {
+        let logged = value.location_source == LocationSource::Log;
+        Self {
+            call: &value.recv_callsign,
+
+            qso_date: value.timestamp.date(),
+            time_on: value.timestamp.time(),
+            time_off: value.timestamp.time(),
+
+            freq: value.freq_tx as f64 / 1000000.0,
+            freq_rx: value.freq_rx as f64 / 1000000.0,
+            band: value.band.as_ref().map(|b| &b.0),
+
+            section: value.section.as_deref(),
+            station_callsign: &value.sent_callsign,
+            contest_id: value.contest_name.as_deref(),
+            mode: &value.mode,
+
+            rst_sent: &value.sent_signal_report,
+            rst_rcvd: &value.recv_signal_report,
+
+            cqz: value.cq_zone,
+            pfx: value.prefix_wpx.as_deref(),
+            operator: value.operator.as_deref(),
+
+            app_n1mm_isrunqso: value.is_run_qso,
+            app_n1mm_claimedqso: value.is_claimed_qso,
+            app_n1mm_points: value.points,
+            app_n1mm_mult1: value.is_mult_1,
+            app_n1mm_mult2: value.is_mult_2,
+            app_n1mm_mult3: value.is_mult_3,
+            app_n1mm_exchange1: value.exchange1.as_deref(),
+            app_n1mm_continent: value.continent.as_deref(),
+            app_n1mm_id: value.id(),
+
+            // coordinates from a lookup or the prefix would come back as if they were logged
+            lat: logged
+                .then_some(value.latitude)
+                .flatten()
+                .and_then(|l| adif::Location::latitude(l.into()).ok()),
+            lon: logged
+                .then_some(value.longitude)
+                .flatten()
+                .and_then(|l| adif::Location::longitude(l.into()).ok()),
+            gridsquare: value.grid_square.as_deref(),
+
+            stx: value.sent_number,
+            srx: value.recv_number,
+            name: value.recv_name.as_deref(),
+            rx_pwr: value.recv_power.as_deref(),
+            qth: value.recv_qth.as_deref(),
+            precedence: value.prec.as_deref(),
+            // the check is the last two digits of a year
+            check: value.ck.map(|c| format!("{:02}", c)),
+            comment: value.comment.as_deref(),
+            cont: value.continent.as_deref(),
+
+            app_n1mm_radio_nr: value.radio_number,
+            app_n1mm_netbiosname: value.net_bios_name.as_deref(),
+        }
+    }
+}
+
+time::serde::format_description!(adif_date, Date, "[year][month][day][end]");
+time::serde::format_description!(adif_time, Time, "[hour][minute][second][end]");
@@ -0,0 +1,53 @@
+use super::{read_adif, write_adif};
+use crate::contact_data::{ContactData, LocationSource};
+
+/// An N1MM Logger+ record of a contact that took two minutes
+const RECORD: &str = "ADIF Export from N1MMLogger.net
+<EOH>
+<CALL:5>K9ABC <QSO_DATE:8>20230624 <TIME_ON:6>180100 <TIME_OFF:6>180300 <BAND:3>20M \
+<STATION_CALLSIGN:4>W9YB <FREQ:6>14.025 <CONTEST_ID:7>ARRL-FD <FREQ_RX:6>14.025 <MODE:2>CW \
+<RST_RCVD:3>599 <RST_SENT:3>599 <OPERATOR:4>W9YB <CQZ:1>4 <STX:1>7 <SECTION:2>IN \
+<APP_N1MM_EXCHANGE1:2>2A <APP_N1MM_POINTS:1>2 <APP_N1MM_RADIO_NR:1>1 <APP_N1MM_CONTINENT:2>NA \
+<APP_N1MM_NETBIOSNAME:4>COMP <APP_N1MM_ISRUNQSO:1>1 <PFX:2>K9 <APP_N1MM_MULT1:1>1 \
+<APP_N1MM_MULT2:1>0 <APP_N1MM_MULT3:1>0 <APP_N1MM_ID:32>09bb67172ab74e5791c8f6c6a3166aae \
+<APP_N1MM_CLAIMEDQSO:1>1 <LAT:11>N041 42.886 <LON:11>W087 37.500 <EOR>
+";
+
+fn import(adif: &str) -> Vec<ContactData> {
+    read_adif(adif)
+        .unwrap()
+        .map(|r| ContactData::from(r.unwrap()))
+        .collect()
+}
+
+fn export(contacts: &[ContactData]) -> String {
+    let mut adif = Vec::new();
+    write_adif(&mut adif, contacts).unwrap();
+    String::from_utf8(adif).unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let logged = import(RECORD).pop().unwrap();
+    assert_eq!(logged.timestamp, time::macros::datetime!(2023-06-24 18:01));
+    assert_eq!(logged.location_source, LocationSource::Log);
+    assert!(logged.latitude.is_some() && logged.longitude.is_some());
+
+    let json = |c: &ContactData| serde_json::to_value(c).unwrap();
+    let adif = export(&[logged.clone()]);
+    assert!(adif.contains("<LAT:"), "{}", adif);
+    let again = import(&adif).pop().unwrap();
+    assert_eq!(json(&again), json(&logged));
+
+    // coordinates that weren't in the log are left for the next lookup
+    for source in [LocationSource::Prefix, LocationSource::HamQTH] {
+        let mut located = logged.clone();
+        located.location_source = source;
+        let adif = export(&[located]);
+        assert!(!adif.contains("<LAT:"), "{}", adif);
+
+        let imported = import(&adif).pop().unwrap();
+        assert_eq!(imported.location_source, LocationSource::NoLocation);
+        assert_eq!((imported.latitude, imported.longitude), (None, None));
+    }
+}
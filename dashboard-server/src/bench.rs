@@ -0,0 +1,134 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{
+    cabrillo,
+    contact_data::ContactData,
+    database::{Database, Scope},
+    revision,
+    rst::RST,
+};
+
+/// The operators `test.sh` asks for
+const OPERATORS: [&str; 4] = ["KD9MEQ", "KD9YWS", "KD9RPL", "K9ETS"];
+
+/// Logs `contacts` contacts into a scratch database, first on its own and then while `pollers`
+/// dashboards query it like `test.sh` does, and prints how long logging a contact took
+#[cfg(not(feature = "postgres"))]
+pub async fn ingest(contacts: usize, pollers: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(contacts > 0, "Nothing to log");
+
+    let path = std::env::temp_dir().join(format!("dashboard-bench-{}.sql", std::process::id()));
+    let path = path
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Temporary directory is not UTF-8"))?;
+    let result = run(path, contacts, pollers).await;
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+    }
+    result
+}
+
+/// As with SQLite, but in the database at `BENCH_DATABASE_URL`, which is left with the contacts
+#[cfg(feature = "postgres")]
+pub async fn ingest(contacts: usize, pollers: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(contacts > 0, "Nothing to log");
+
+    let url = std::env::var("BENCH_DATABASE_URL")
+        .map_err(|_| anyhow::anyhow!("BENCH_DATABASE_URL must be set to a scratch database"))?;
+    run(&url, contacts, pollers).await
+}
+
+async fn run(url: &str, contacts: usize, pollers: usize) -> anyhow::Result<()> {
+    let db = Database::open(url)?;
+    let contest = db.seen_contest(0, "BENCH", None).await?;
+
+    let mut number = 0;
+    for pollers in [0, pollers] {
+        let stop = Arc::new(AtomicBool::new(false));
+        let polls = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for poller in 0..pollers {
+            let (db, stop, polls) = (db.clone(), stop.clone(), polls.clone());
+            tasks.spawn(async move {
+                for i in poller.. {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    // `mostRecent` for an operator and then `entries`
+                    let operator = OPERATORS[i % OPERATORS.len()].to_owned();
+                    let count = (i * 37 % 600) as u32;
+                    db.most_recent(&Scope::Current, Some(count), Some(false), Some(operator))
+                        .await?;
+                    db.most_recent(&Scope::Current, None, None, None).await?;
+                    polls.fetch_add(1, Ordering::Relaxed);
+                }
+                anyhow::Ok(())
+            });
+        }
+
+        let mut latencies = Vec::with_capacity(contacts);
+        for _ in 0..contacts {
+            number += 1;
+            let contact = contact(contest, number)?;
+            let start = std::time::Instant::now();
+            db.update(&contact, &revision::Origin::default(), true)
+                .await?;
+            latencies.push(start.elapsed());
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        while let Some(res) = tasks.join_next().await {
+            res??;
+        }
+
+        latencies.sort();
+        let percentile = |p: usize| latencies[(latencies.len() - 1) * p / 100];
+        println!(
+            "{:>4} pollers: p50 {:?}, p90 {:?}, p99 {:?}, max {:?} over {} contacts, {} polls",
+            pollers,
+            percentile(50),
+            percentile(90),
+            percentile(99),
+            percentile(100),
+            contacts,
+            polls.load(Ordering::Relaxed)
+        );
+    }
+
+    Ok(())
+}
+
+/// A live contact of `contest`, `number` makes it unique
+fn contact(contest: i32, number: usize) -> anyhow::Result<ContactData> {
+    let qso = cabrillo::Qso {
+        frequency: 14_025_000,
+        mode: "CW".to_owned(),
+        timestamp: time::macros::datetime!(2023-11-25 00:00)
+            + time::Duration::seconds(number as i64),
+
+        sent_callsign: "W9YB".to_owned(),
+        sent_signal_report: RST::new(5, 9, Some(9))?,
+        recv_callsign: format!("W{}AW", number % 10),
+        recv_signal_report: RST::new(5, 9, Some(9))?,
+
+        exchange: None,
+        section: None,
+        cq_zone: Some(4),
+        sent_number: None,
+        recv_number: None,
+        prec: None,
+        ck: None,
+
+        claimed: true,
+    };
+
+    let mut contact = ContactData::from_cabrillo("bench", number, Some("BENCH"), qso);
+    contact.log_name = None;
+    contact.contest_id = Some(contest);
+    contact.operator = Some(OPERATORS[number % OPERATORS.len()].to_owned());
+    Ok(contact)
+}
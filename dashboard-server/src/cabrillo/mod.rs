@@ -0,0 +1,338 @@
+use std::fmt::Write;
+
+use crate::contact_data::ContactData;
+
+mod reader;
+#[cfg(test)]
+mod test;
+
+pub use reader::{parse, Log, Qso};
+
+/// Contests with a QSO line template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum Contest {
+    /// ARRL Field Day, class and section
+    FieldDay,
+    /// ARRL Sweepstakes, serial, precedence, check and section
+    Sweepstakes,
+    /// CQ World Wide DX, signal report and CQ zone
+    CqWw,
+    /// CQ WPX, signal report and serial
+    Wpx,
+}
+
+impl Contest {
+    /// The `CONTEST:` name, some contests have one per mode
+    pub fn name(&self, mode: &str) -> String {
+        let (name, has_modes) = match self {
+            Self::FieldDay => ("ARRL-FD", false),
+            Self::Sweepstakes => ("ARRL-SS", true),
+            Self::CqWw => ("CQ-WW", true),
+            Self::Wpx => ("CQ-WPX", true),
+        };
+        match (has_modes, mode) {
+            (false, _) => name.to_owned(),
+            // FM is phone in the contests with modes
+            (true, "PH" | "FM") => format!("{}-SSB", name),
+            (true, "RY") if *self != Self::Sweepstakes => format!("{}-RTTY", name),
+            (true, _) => format!("{}-CW", name),
+        }
+    }
+
+    /// The contest of a `CONTEST:` name, ignoring the mode
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        [
+            ("ARRL-FD", Self::FieldDay),
+            ("ARRL-SS", Self::Sweepstakes),
+            ("CQ-WW", Self::CqWw),
+            ("CQ-WPX", Self::Wpx),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, contest)| contest)
+    }
+}
+
+impl std::str::FromStr for Contest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fd" | "field-day" | "arrl-fd" => Ok(Self::FieldDay),
+            "ss" | "sweepstakes" | "arrl-ss" => Ok(Self::Sweepstakes),
+            "cqww" | "cq-ww" => Ok(Self::CqWw),
+            "wpx" | "cq-wpx" => Ok(Self::Wpx),
+            _ => Err(anyhow::anyhow!("Unknown contest `{}`", s)),
+        }
+    }
+}
+
+/// Header fields, anything not given is worked out from the log where possible
+#[derive(Debug, Clone, Default, async_graphql::InputObject)]
+pub struct Header {
+    /// Defaults to the station callsign of most contacts
+    pub callsign: Option<String>,
+    /// Defaults to the contest name for the most used mode
+    pub contest: Option<String>,
+    /// Our own exchange without the signal report or serial, such as `3A IN` for Field Day
+    #[graphql(default)]
+    pub exchange: String,
+
+    pub category_operator: Option<String>,
+    pub category_assisted: Option<String>,
+    pub category_band: Option<String>,
+    pub category_mode: Option<String>,
+    pub category_power: Option<String>,
+    pub category_station: Option<String>,
+    pub category_transmitter: Option<String>,
+
+    /// Defaults to every operator in the log
+    pub operators: Option<Vec<String>>,
+    /// Defaults to the points multiplied by the number of multipliers
+    pub claimed_score: Option<i64>,
+}
+
+impl Header {
+    /// Reads `--name value` pairs, such as `--category-operator MULTI-OP`
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut header = Self::default();
+        let mut args = args.into_iter();
+        while let Some(name) = args.next() {
+            let Some(value) = args.next() else {
+                return Err(anyhow::anyhow!("Missing value for `{}`", name));
+            };
+            let field = match name.as_str() {
+                "--callsign" => &mut header.callsign,
+                "--contest" => &mut header.contest,
+                "--exchange" => {
+                    header.exchange = value;
+                    continue;
+                }
+                "--category-operator" => &mut header.category_operator,
+                "--category-assisted" => &mut header.category_assisted,
+                "--category-band" => &mut header.category_band,
+                "--category-mode" => &mut header.category_mode,
+                "--category-power" => &mut header.category_power,
+                "--category-station" => &mut header.category_station,
+                "--category-transmitter" => &mut header.category_transmitter,
+                "--operators" => {
+                    header.operators = Some(value.split_whitespace().map(str::to_owned).collect());
+                    continue;
+                }
+                "--claimed-score" => {
+                    header.claimed_score = Some(value.parse()?);
+                    continue;
+                }
+                _ => return Err(anyhow::anyhow!("Unknown option `{}`", name)),
+            };
+            *field = Some(value);
+        }
+        Ok(header)
+    }
+}
+
+/// Writes a Cabrillo 3.0 log, contacts N1MM did not claim are written as `X-QSO`
+pub fn to_string(contest: Contest, header: &Header, contacts: &[ContactData]) -> String {
+    let mut log = String::new();
+    let mut line = |name: &str, value: &str| match value {
+        "" => writeln!(log, "{}:", name).unwrap(),
+        _ => writeln!(log, "{}: {}", name, value).unwrap(),
+    };
+
+    line("START-OF-LOG", "3.0");
+    line(
+        "CREATED-BY",
+        concat!("dashboard-server ", env!("CARGO_PKG_VERSION")),
+    );
+
+    let callsign = header
+        .callsign
+        .clone()
+        .or_else(|| most_common(contacts.iter().map(|c| c.sent_callsign.as_str())))
+        .unwrap_or_default();
+    line("CALLSIGN", &callsign);
+
+    let contest_name = header.contest.clone().unwrap_or_else(|| {
+        let mode = most_common(contacts.iter().map(|c| mode(&c.mode)));
+        contest.name(mode.as_deref().unwrap_or("CW"))
+    });
+    line("CONTEST", &contest_name);
+
+    for (name, value) in [
+        ("CATEGORY-OPERATOR", &header.category_operator),
+        ("CATEGORY-ASSISTED", &header.category_assisted),
+        ("CATEGORY-BAND", &header.category_band),
+        ("CATEGORY-MODE", &header.category_mode),
+        ("CATEGORY-POWER", &header.category_power),
+        ("CATEGORY-STATION", &header.category_station),
+        ("CATEGORY-TRANSMITTER", &header.category_transmitter),
+    ] {
+        if let Some(value) = value {
+            line(name, value);
+        }
+    }
+
+    line(
+        "CLAIMED-SCORE",
+        &header
+            .claimed_score
+            .unwrap_or_else(|| claimed_score(contacts))
+            .to_string(),
+    );
+
+    let operators = header.operators.clone().unwrap_or_else(|| {
+        let mut operators: Vec<String> = contacts
+            .iter()
+            .filter_map(|c| c.operator.clone())
+            .filter(|o| !o.is_empty())
+            .collect();
+        operators.sort();
+        operators.dedup();
+        operators
+    });
+    if !operators.is_empty() {
+        line("OPERATORS", &operators.join(" "));
+    }
+
+    for (serial, contact) in (1..).zip(contacts) {
+        let tag = if contact.is_claimed_qso {
+            "QSO"
+        } else {
+            "X-QSO"
+        };
+        line(tag, &qso(contest, &header.exchange, serial, contact));
+    }
+
+    line("END-OF-LOG", "");
+    log
+}
+
+/// The QSO line after the tag, with our exchange, `serial` is sent for contacts that don't know
+/// the serial they were sent
+pub fn qso(contest: Contest, exchange: &str, serial: u32, contact: &ContactData) -> String {
+    let sent_rst = contact.sent_signal_report.to_string();
+    let recv_rst = contact.recv_signal_report.to_string();
+    let sent_serial = contact
+        .sent_number
+        .map_or_else(|| serial.to_string(), |n| n.to_string());
+    let cq_zone = contact.cq_zone.to_string();
+    let exchange1 = contact.exchange1.as_deref().unwrap_or_default();
+    let section = contact.section.as_deref().unwrap_or_default();
+
+    // the received serial, precedence and check, from what N1MM logged them as when they're known
+    let recv_serial = contact
+        .recv_number
+        .map_or_else(|| exchange1.to_owned(), |n| n.to_string());
+    let recv_sweepstakes = match (contact.recv_number, &contact.prec, contact.ck) {
+        (Some(number), Some(prec), Some(ck)) => format!("{} {} {:02}", number, prec, ck),
+        _ => exchange1.to_owned(),
+    };
+
+    let (sent, received): ([&str; 2], [&str; 2]) = match contest {
+        Contest::FieldDay => ([exchange, ""], [exchange1, section]),
+        Contest::Sweepstakes => ([&sent_serial, exchange], [&recv_sweepstakes, section]),
+        Contest::CqWw => ([&sent_rst, exchange], [&recv_rst, &cq_zone]),
+        Contest::Wpx => ([&sent_rst, &sent_serial], [&recv_rst, &recv_serial]),
+    };
+    let join = |parts: [&str; 2]| {
+        parts
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let timestamp = contact
+        .timestamp
+        .format(time::macros::format_description!(
+            "[year]-[month]-[day] [hour][minute]"
+        ))
+        .unwrap();
+    format!(
+        "{:>5} {} {} {:<13} {:<10} {:<13} {}",
+        frequency(contact.freq_tx),
+        mode(&contact.mode),
+        timestamp,
+        contact.sent_callsign,
+        join(sent),
+        contact.recv_callsign,
+        join(received)
+    )
+    .trim_end()
+    .to_owned()
+}
+
+/// Band designators for QSO lines above 30 MHz
+static DESIGNATORS: &[(adif::Band, &str)] = &[
+    (adif::Band::M6, "50"),
+    (adif::Band::M4, "70"),
+    (adif::Band::M2, "144"),
+    (adif::Band::M1_25, "222"),
+    (adif::Band::Cm70, "432"),
+    (adif::Band::Cm33, "902"),
+    (adif::Band::Cm23, "1.2G"),
+    (adif::Band::Cm13, "2.3G"),
+    (adif::Band::Cm9, "3.4G"),
+    (adif::Band::Cm6, "5.7G"),
+    (adif::Band::Cm3, "10G"),
+    (adif::Band::Cm1_25, "24G"),
+    (adif::Band::Mm6, "47G"),
+    (adif::Band::Mm4, "75G"),
+    (adif::Band::Mm2_5, "122G"),
+    (adif::Band::Mm2, "134G"),
+    (adif::Band::Mm1, "241G"),
+];
+
+/// kHz below 30 MHz, and the band above it
+pub fn frequency(hz: i64) -> String {
+    let khz = hz / 1000;
+    if khz < 30_000 {
+        return khz.to_string();
+    }
+
+    let band = adif::Band::from_frequency(hz as f64 / 1_000_000.0);
+    DESIGNATORS
+        .iter()
+        .find(|(b, _)| Some(b) == band.as_ref())
+        .map_or("LIGHT", |(_, designator)| designator)
+        .to_owned()
+}
+
+/// The Cabrillo mode for an ADIF mode or submode
+pub fn mode(mode: &str) -> &'static str {
+    use adif::Mode;
+    let mode = match Mode::from(mode) {
+        Mode::Unknown(m) => Mode::from_submode(&m).unwrap_or(Mode::Unknown(m)),
+        m => m,
+    };
+    match mode {
+        Mode::Cw => "CW",
+        Mode::Ssb | Mode::Am | Mode::DigitalVoice => "PH",
+        Mode::Fm => "FM",
+        Mode::Rtty => "RY",
+        _ => "DG",
+    }
+}
+
+/// Points of the claimed contacts multiplied by their multipliers
+fn claimed_score(contacts: &[ContactData]) -> i64 {
+    let claimed = contacts.iter().filter(|c| c.is_claimed_qso);
+    let points: i64 = claimed.clone().map(|c| i64::from(c.points)).sum();
+    let multipliers = claimed
+        .flat_map(|c| [c.is_mult_1, c.is_mult_2, c.is_mult_3])
+        .filter(|m| *m)
+        .count() as i64;
+    points * multipliers.max(1)
+}
+
+fn most_common<'s>(values: impl Iterator<Item = &'s str>) -> Option<String> {
+    let mut counts = std::collections::HashMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(value, count)| (*count, std::cmp::Reverse(*value)))
+        .map(|(value, _)| value.to_owned())
+}
@@ -0,0 +1,225 @@
+use super::{Contest, DESIGNATORS};
+use crate::rst::RST;
+
+/// A Cabrillo log read with the QSO template of its contest
+#[derive(Debug)]
+pub struct Log {
+    /// Every tag before the first QSO, in order, tags such as `SOAPBOX` can repeat
+    pub header: Vec<(String, String)>,
+    pub contest: Contest,
+    pub qsos: Vec<Qso>,
+    /// Line numbers and errors of QSO lines that could not be read
+    pub skipped: Vec<(usize, anyhow::Error)>,
+}
+
+impl Log {
+    /// The first value of a header tag, ignoring case
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        self.header
+            .iter()
+            .find(|(t, _)| t.eq_ignore_ascii_case(tag))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A QSO line with its exchanges split into the fields N1MM uses
+#[derive(Debug, Clone, PartialEq)]
+pub struct Qso {
+    pub frequency: i64,
+    /// ADIF mode, or the Cabrillo mode if there is no equivalent
+    pub mode: String,
+    pub timestamp: time::PrimitiveDateTime,
+
+    pub sent_callsign: String,
+    pub sent_signal_report: RST,
+    pub recv_callsign: String,
+    pub recv_signal_report: RST,
+
+    /// Received exchange apart from the signal report and section, as in N1MM's `Exchange1`
+    pub exchange: Option<String>,
+    pub section: Option<String>,
+    pub cq_zone: Option<i16>,
+    pub sent_number: Option<i32>,
+    pub recv_number: Option<i32>,
+    /// Sweepstakes precedence and check
+    pub prec: Option<String>,
+    pub ck: Option<i32>,
+
+    /// `X-QSO` lines are not claimed
+    pub claimed: bool,
+}
+
+/// Reads a Cabrillo 2.0 or 3.0 log, `contest` overrides the `CONTEST:` tag
+pub fn parse(text: &str, contest: Option<Contest>) -> anyhow::Result<Log> {
+    let mut header = Vec::new();
+    let mut lines = Vec::new();
+    let mut started = false;
+
+    for (number, line) in (1..).zip(text.lines()) {
+        let Some((tag, value)) = line.split_once(':') else {
+            continue;
+        };
+        let tag = tag.trim().to_ascii_uppercase();
+        let value = value.trim();
+        match tag.as_str() {
+            "START-OF-LOG" => started = true,
+            "END-OF-LOG" => break,
+            "QSO" | "X-QSO" => lines.push((number, tag == "QSO", value)),
+            _ if lines.is_empty() => header.push((tag, value.to_owned())),
+            _ => {}
+        }
+    }
+
+    if !started {
+        return Err(anyhow::anyhow!("Not a Cabrillo log, no `START-OF-LOG:`"));
+    }
+
+    let contest = match contest {
+        Some(c) => c,
+        None => {
+            let name = header
+                .iter()
+                .find(|(t, _)| t == "CONTEST")
+                .map(|(_, v)| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("No `CONTEST:` in the log"))?;
+            Contest::from_name(name)
+                .ok_or_else(|| anyhow::anyhow!("No QSO template for contest `{}`", name))?
+        }
+    };
+
+    let mut qsos = Vec::new();
+    let mut skipped = Vec::new();
+    for (number, claimed, line) in lines {
+        match parse_qso(contest, line, claimed) {
+            Ok(qso) => qsos.push(qso),
+            Err(e) => skipped.push((number, e)),
+        }
+    }
+
+    Ok(Log {
+        header,
+        contest,
+        qsos,
+        skipped,
+    })
+}
+
+/// Parses a QSO line after the tag
+pub fn parse_qso(contest: Contest, line: &str, claimed: bool) -> anyhow::Result<Qso> {
+    let (sent_length, received_length) = match contest {
+        Contest::Sweepstakes => (4, 4),
+        Contest::FieldDay | Contest::CqWw | Contest::Wpx => (2, 2),
+    };
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    // a transmitter number may follow the received exchange
+    let expected = 6 + sent_length + received_length;
+    if tokens.len() != expected && tokens.len() != expected + 1 {
+        return Err(anyhow::anyhow!(
+            "Expected {} fields for {:?}, found {}",
+            expected,
+            contest,
+            tokens.len()
+        ));
+    }
+
+    let frequency = parse_frequency(tokens[0])?;
+    let mode = match tokens[1] {
+        "CW" => "CW",
+        "PH" => "SSB",
+        "FM" => "FM",
+        "RY" => "RTTY",
+        m => m,
+    };
+    let date = time::Date::parse(
+        tokens[2],
+        time::macros::format_description!("[year]-[month]-[day]"),
+    )?;
+    let time = time::Time::parse(
+        tokens[3],
+        time::macros::format_description!("[hour][minute]"),
+    )?;
+
+    let sent = &tokens[5..(5 + sent_length)];
+    let received = &tokens[(6 + sent_length)..expected];
+
+    // templates without a signal report assume a perfect one
+    let default_rst = match mode {
+        "SSB" | "FM" => RST::new(5, 9, None)?,
+        _ => RST::new(5, 9, Some(9))?,
+    };
+    let (sent_signal_report, recv_signal_report, exchange, section, cq_zone) = match contest {
+        Contest::FieldDay => (
+            default_rst.clone(),
+            default_rst,
+            Some(received[0]),
+            Some(received[1]),
+            None,
+        ),
+        Contest::Sweepstakes => (
+            default_rst.clone(),
+            default_rst,
+            None,
+            Some(received[3]),
+            None,
+        ),
+        Contest::CqWw => (
+            RST::try_from(sent[0])?,
+            RST::try_from(received[0])?,
+            None,
+            None,
+            Some(received[1].parse()?),
+        ),
+        Contest::Wpx => (
+            RST::try_from(sent[0])?,
+            RST::try_from(received[0])?,
+            Some(received[1]),
+            None,
+            None,
+        ),
+    };
+    let exchange = match contest {
+        Contest::Sweepstakes => Some(received[..3].join(" ")),
+        _ => exchange.map(str::to_owned),
+    };
+    let (sent_number, recv_number) = match contest {
+        Contest::Sweepstakes => (Some(sent[0].parse()?), Some(received[0].parse()?)),
+        Contest::Wpx => (Some(sent[1].parse()?), Some(received[1].parse()?)),
+        Contest::FieldDay | Contest::CqWw => (None, None),
+    };
+    let (prec, ck) = match contest {
+        Contest::Sweepstakes => (Some(received[1].to_owned()), Some(received[2].parse()?)),
+        _ => (None, None),
+    };
+
+    Ok(Qso {
+        frequency,
+        mode: mode.to_owned(),
+        timestamp: time::PrimitiveDateTime::new(date, time),
+        sent_callsign: tokens[4].to_owned(),
+        sent_signal_report,
+        recv_callsign: tokens[5 + sent_length].to_owned(),
+        recv_signal_report,
+        exchange,
+        section: section.map(str::to_owned),
+        cq_zone,
+        sent_number,
+        recv_number,
+        prec,
+        ck,
+        claimed,
+    })
+}
+
+/// Hz from kHz, or the lower edge of the band for a band designator
+fn parse_frequency(value: &str) -> anyhow::Result<i64> {
+    if let Some((band, _)) = DESIGNATORS.iter().find(|(_, d)| *d == value) {
+        let info = band.info().expect("designators are known bands");
+        return Ok((info.lower * 1_000_000.0).round() as i64);
+    }
+
+    let khz: i64 = value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid frequency `{}`", value))?;
+    Ok(khz * 1000)
+}
@@ -0,0 +1,272 @@
+use super::{frequency, mode, parse, qso, to_string, Contest, Header};
+use crate::{contact_data::ContactData, rst::RST};
+
+#[test]
+fn frequencies() {
+    assert_eq!(frequency(14_025_300), "14025");
+    assert_eq!(frequency(1_830_000), "1830");
+    assert_eq!(frequency(50_125_000), "50");
+    assert_eq!(frequency(144_200_000), "144");
+    assert_eq!(frequency(1_296_100_000), "1.2G");
+}
+
+#[test]
+fn modes() {
+    assert_eq!(mode("CW"), "CW");
+    assert_eq!(mode("USB"), "PH");
+    assert_eq!(mode("ssb"), "PH");
+    assert_eq!(mode("RTTY"), "RY");
+    assert_eq!(mode("FT8"), "DG");
+    assert_eq!(mode("PSK31"), "DG");
+}
+
+#[test]
+fn contest_names() {
+    assert_eq!(Contest::FieldDay.name("PH"), "ARRL-FD");
+    assert_eq!(Contest::CqWw.name("RY"), "CQ-WW-RTTY");
+    assert_eq!(Contest::Wpx.name("PH"), "CQ-WPX-SSB");
+    assert_eq!(Contest::Sweepstakes.name("FM"), "ARRL-SS-SSB");
+    assert_eq!(Contest::CqWw.name("FM"), "CQ-WW-SSB");
+    assert_eq!(Contest::Sweepstakes.name("RY"), "ARRL-SS-CW");
+    assert_eq!("cq-ww".parse::<Contest>().unwrap(), Contest::CqWw);
+    assert!("iaru".parse::<Contest>().is_err());
+}
+
+#[test]
+fn header_args() {
+    let args = [
+        "--callsign",
+        "W9YB",
+        "--exchange",
+        "3A IN",
+        "--category-operator",
+        "MULTI-OP",
+        "--operators",
+        "K9ET W1AW",
+        "--claimed-score",
+        "1234",
+    ];
+    let header = Header::from_args(args.map(str::to_owned)).unwrap();
+    assert_eq!(header.callsign.as_deref(), Some("W9YB"));
+    assert_eq!(header.exchange, "3A IN");
+    assert_eq!(header.category_operator.as_deref(), Some("MULTI-OP"));
+    assert_eq!(
+        header.operators,
+        Some(vec!["K9ET".to_owned(), "W1AW".to_owned()])
+    );
+    assert_eq!(header.claimed_score, Some(1234));
+
+    assert!(Header::from_args(["--callsign".to_owned()]).is_err());
+    assert!(Header::from_args(["--nope".to_owned(), "x".to_owned()]).is_err());
+}
+
+const LOG: &str = "START-OF-LOG: 3.0
+CALLSIGN: W9YB
+CONTEST: ARRL-SS-CW
+SOAPBOX: first line
+SOAPBOX: second line
+QSO: 14025 CW 2023-11-04 2101 W9YB          1 A 63 IN     K9ET          12 B 99 WI
+QSO:    50 PH 2023-11-04 2102 W9YB          2 A 63 IN     W1AW          7 Q 47 CT
+X-QSO:  7012 CW 2023-11-04 2103 W9YB        3 A 63 IN     KD9M          40 M 01 IL
+QSO: 14025 CW 2023-11-04 2104 W9YB          4 A 63 IN     N0CALL
+QSO: 14025 XX 2023-11-04 2x05 W9YB          5 A 63 IN     KD9M          41 M 01 IL
+END-OF-LOG:
+QSO: 14025 CW 2023-11-04 2106 W9YB          6 A 63 IN     W9ABC         1 A 00 IN
+";
+
+#[test]
+fn read_log() {
+    let log = parse(LOG, None).unwrap();
+    assert_eq!(log.contest, Contest::Sweepstakes);
+    assert_eq!(log.get("callsign"), Some("W9YB"));
+    assert_eq!(log.get("SOAPBOX"), Some("first line"));
+    assert_eq!(
+        log.qsos
+            .iter()
+            .map(|q| (q.recv_callsign.as_str(), q.claimed))
+            .collect::<Vec<_>>(),
+        vec![("K9ET", true), ("W1AW", true), ("KD9M", false)]
+    );
+    assert_eq!(
+        log.skipped
+            .iter()
+            .map(|(line, _)| *line)
+            .collect::<Vec<_>>(),
+        vec![9, 10]
+    );
+
+    let qso = &log.qsos[0];
+    assert_eq!(qso.frequency, 14_025_000);
+    assert_eq!(qso.mode, "CW");
+    assert_eq!(qso.timestamp, time::macros::datetime!(2023-11-04 21:01));
+    assert_eq!(qso.exchange.as_deref(), Some("12 B 99"));
+    assert_eq!(qso.section.as_deref(), Some("WI"));
+    assert_eq!((qso.sent_number, qso.recv_number), (Some(1), Some(12)));
+    assert_eq!((qso.prec.as_deref(), qso.ck), (Some("B"), Some(99)));
+
+    // band designators read as the lower edge of the band
+    assert_eq!(log.qsos[1].frequency, 50_000_000);
+    assert_eq!(log.qsos[1].mode, "SSB");
+    assert_eq!(
+        log.qsos[1].recv_signal_report,
+        RST::new(5, 9, None).unwrap()
+    );
+}
+
+#[test]
+fn templates() {
+    let qso = |contest: Contest, line: &str| super::reader::parse_qso(contest, line, true);
+
+    let fd = qso(
+        Contest::FieldDay,
+        "144 PH 2023-06-24 1801 W9YB 4A IN K1ABC 2A CT",
+    )
+    .unwrap();
+    assert_eq!(fd.frequency, 144_000_000);
+    assert_eq!(fd.exchange.as_deref(), Some("2A"));
+    assert_eq!(fd.section.as_deref(), Some("CT"));
+
+    let cqww = qso(
+        Contest::CqWw,
+        "14025 CW 2023-11-25 0001 W9YB 599 4 DL1ABC 579 14 1",
+    )
+    .unwrap();
+    assert_eq!(cqww.cq_zone, Some(14));
+    assert_eq!(cqww.recv_signal_report, RST::new(5, 7, Some(9)).unwrap());
+
+    let wpx = qso(
+        Contest::Wpx,
+        "21250 PH 2023-03-25 1200 W9YB 59 1 JA1XYZ 57 123",
+    )
+    .unwrap();
+    assert_eq!(wpx.exchange.as_deref(), Some("123"));
+    assert_eq!((wpx.sent_number, wpx.recv_number), (Some(1), Some(123)));
+    assert_eq!(wpx.sent_signal_report, RST::new(5, 9, None).unwrap());
+
+    assert!(qso(
+        Contest::CqWw,
+        "14025 CW 2023-11-25 0001 W9YB 599 4 DL1ABC 579 xx"
+    )
+    .is_err());
+    assert!(qso(Contest::FieldDay, "14025 CW 2023-11-25 0001 W9YB 4A IN").is_err());
+}
+
+#[test]
+fn contest_from_header() {
+    assert_eq!(Contest::from_name("cq-ww-rtty"), Some(Contest::CqWw));
+    assert_eq!(Contest::from_name("ARRL-FD"), Some(Contest::FieldDay));
+    assert_eq!(Contest::from_name("IARU-HF"), None);
+
+    assert!(parse("CONTEST: CQ-WW-CW\nEND-OF-LOG:\n", None).is_err());
+    assert!(parse("START-OF-LOG: 3.0\nCONTEST: IARU-HF\n", None).is_err());
+    let log = parse("START-OF-LOG: 3.0\nCONTEST: IARU-HF\n", Some(Contest::Wpx)).unwrap();
+    assert_eq!(log.contest, Contest::Wpx);
+    assert!(log.qsos.is_empty());
+}
+
+/// A contact as if it were logged with the details of a QSO line
+fn contact(contest: Contest, line: &str) -> ContactData {
+    let qso = super::reader::parse_qso(contest, line, true).unwrap();
+    ContactData::from_cabrillo("test", 1, None, qso)
+}
+
+#[test]
+fn qso_lines() {
+    let fd = contact(
+        Contest::FieldDay,
+        "144 PH 2023-06-24 1801 W9YB 4A IN K1ABC 2A CT",
+    );
+    assert_eq!(
+        qso(Contest::FieldDay, "4A IN", 7, &fd),
+        "  144 PH 2023-06-24 1801 W9YB          4A IN      K1ABC         2A CT"
+    );
+
+    let mut ss = contact(
+        Contest::Sweepstakes,
+        "14025 CW 2023-11-04 2101 W9YB 1 A 63 IN K9ET 12 B 99 WI",
+    );
+    ss.exchange1 = None;
+    assert_eq!(
+        qso(Contest::Sweepstakes, "A 63 IN", 7, &ss),
+        "14025 CW 2023-11-04 2101 W9YB          1 A 63 IN  K9ET          12 B 99 WI"
+    );
+    // the position in the log when the serial sent isn't known
+    ss.sent_number = None;
+    assert_eq!(
+        qso(Contest::Sweepstakes, "A 63 IN", 7, &ss),
+        "14025 CW 2023-11-04 2101 W9YB          7 A 63 IN  K9ET          12 B 99 WI"
+    );
+
+    let cqww = contact(
+        Contest::CqWw,
+        "14025 CW 2023-11-25 0001 W9YB 599 4 DL1ABC 579 14 1",
+    );
+    assert_eq!(
+        qso(Contest::CqWw, "4", 7, &cqww),
+        "14025 CW 2023-11-25 0001 W9YB          599 4      DL1ABC        579 14"
+    );
+
+    let mut wpx = contact(
+        Contest::Wpx,
+        "21250 PH 2023-03-25 1200 W9YB 59 1 JA1XYZ 57 123",
+    );
+    wpx.exchange1 = None;
+    assert_eq!(
+        qso(Contest::Wpx, "", 7, &wpx),
+        "21250 PH 2023-03-25 1200 W9YB          59 1       JA1XYZ        57 123"
+    );
+}
+
+#[test]
+fn header_block() {
+    let mut run = contact(
+        Contest::Wpx,
+        "21250 PH 2023-03-25 1200 W9YB 59 1 JA1XYZ 57 123",
+    );
+    run.operator = Some("W1AW".to_owned());
+    run.points = 3;
+    run.is_mult_1 = true;
+    let mut unclaimed = contact(
+        Contest::Wpx,
+        "21250 PH 2023-03-25 1201 W9YB 59 2 JA1XYZ 57 124",
+    );
+    unclaimed.operator = Some("K9ET".to_owned());
+    unclaimed.is_claimed_qso = false;
+    unclaimed.points = 5;
+    unclaimed.is_mult_2 = true;
+    let contacts = [run, unclaimed];
+
+    let header = Header {
+        category_operator: Some("MULTI-OP".to_owned()),
+        ..Header::default()
+    };
+    assert_eq!(
+        to_string(Contest::Wpx, &header, &contacts),
+        concat!(
+            "START-OF-LOG: 3.0\n",
+            "CREATED-BY: dashboard-server ",
+            env!("CARGO_PKG_VERSION"),
+            "\n",
+            "CALLSIGN: W9YB\n",
+            "CONTEST: CQ-WPX-SSB\n",
+            "CATEGORY-OPERATOR: MULTI-OP\n",
+            "CLAIMED-SCORE: 3\n",
+            "OPERATORS: K9ET W1AW\n",
+            "QSO: 21250 PH 2023-03-25 1200 W9YB          59 1       JA1XYZ        57 123\n",
+            "X-QSO: 21250 PH 2023-03-25 1201 W9YB          59 2       JA1XYZ        57 124\n",
+            "END-OF-LOG:\n",
+        )
+    );
+
+    let header = Header {
+        operators: Some(vec!["W9YB".to_owned()]),
+        claimed_score: Some(1234),
+        ..Header::default()
+    };
+    let log = to_string(Contest::Wpx, &header, &contacts);
+    assert!(
+        log.contains("\nCLAIMED-SCORE: 1234\nOPERATORS: W9YB\n"),
+        "{}",
+        log
+    );
+}
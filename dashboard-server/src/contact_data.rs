@@ -10,10 +10,12 @@ use crate::rst;
     diesel::Queryable,
     diesel::Selectable,
     diesel::Insertable,
+    Serialize,
+    Deserialize,
 )]
 #[graphql(complex)]
 #[diesel(table_name = crate::schema::contacts)]
-#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(check_for_backend(crate::database::DbBackend))]
 pub struct ContactData {
     #[diesel(column_name = "id")]
     #[graphql(name = "id")]
@@ -28,7 +30,6 @@ pub struct ContactData {
     pub timestamp: time::PrimitiveDateTime,
 
     pub mode: String,
-    // band: String,
     pub freq_rx: i64,
     pub freq_tx: i64,
 
@@ -51,6 +52,37 @@ pub struct ContactData {
     pub location_source: LocationSource,
     pub latitude: Option<f32>,
     pub longitude: Option<f32>,
+
+    pub band: Option<Band>,
+
+    /// The imported log this contact is from, `None` for the live contest
+    pub log_name: Option<String>,
+    /// The contest of a live contact, see [`crate::contest::Contest`]
+    pub contest_id: Option<i32>,
+
+    pub grid_square: Option<String>,
+    pub sent_number: Option<i32>,
+    pub recv_number: Option<i32>,
+
+    pub recv_name: Option<String>,
+    pub recv_power: Option<String>,
+    pub recv_qth: Option<String>,
+    /// Sweepstakes precedence and check
+    pub prec: Option<String>,
+    pub ck: Option<i32>,
+    pub comment: Option<String>,
+
+    pub continent: Option<String>,
+    pub country_prefix: Option<String>,
+
+    /// The radio and computer that logged the contact
+    pub radio_number: Option<i32>,
+    pub station_name: Option<String>,
+    pub net_bios_name: Option<String>,
+
+    /// When the contact was deleted, deleted contacts are kept for their history
+    #[graphql(skip)]
+    pub deleted_at: Option<time::PrimitiveDateTime>,
 }
 
 #[async_graphql::ComplexObject]
@@ -62,12 +94,89 @@ impl ContactData {
             ))
             .unwrap()
     }
+
+    /// The operator of `recv_callsign`, once HamQTH has been asked
+    async fn profile(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<Option<crate::profile::Profile>> {
+        let database = ctx.data::<crate::database::Database>()?;
+        Ok(database.profile(&self.recv_callsign).await?)
+    }
 }
 
 impl ContactData {
     pub fn id(&self) -> Option<&str> {
         self.n1mm_id.as_deref()
     }
+
+    /// A contact of an imported Cabrillo log, `number` makes the ID unique within the log
+    pub fn from_cabrillo(
+        log_name: &str,
+        number: usize,
+        contest_name: Option<&str>,
+        qso: crate::cabrillo::Qso,
+    ) -> Self {
+        Self {
+            n1mm_id: Some(format!("{}:{}", log_name, number)),
+
+            recv_callsign: qso.recv_callsign,
+            sent_callsign: qso.sent_callsign,
+
+            recv_signal_report: qso.recv_signal_report,
+            sent_signal_report: qso.sent_signal_report,
+            timestamp: qso.timestamp,
+
+            mode: qso.mode,
+            freq_rx: qso.frequency,
+            freq_tx: qso.frequency,
+
+            exchange1: qso.exchange,
+            section: qso.section,
+            prefix_wpx: None,
+            cq_zone: qso.cq_zone.unwrap_or_default(),
+
+            contest_name: contest_name.map(str::to_owned),
+            operator: None,
+
+            // Cabrillo has no scoring, only whether the contact is claimed
+            is_mult_1: false,
+            is_mult_2: false,
+            is_mult_3: false,
+
+            is_run_qso: false,
+            is_claimed_qso: qso.claimed,
+            points: 0,
+
+            location_source: LocationSource::NoLocation,
+            latitude: None,
+            longitude: None,
+
+            band: adif::Band::from_frequency(qso.frequency as f64 / 1_000_000.0).map(Band),
+            log_name: Some(log_name.to_owned()),
+            contest_id: None,
+
+            grid_square: None,
+            sent_number: qso.sent_number,
+            recv_number: qso.recv_number,
+
+            recv_name: None,
+            recv_power: None,
+            recv_qth: None,
+            prec: qso.prec,
+            ck: qso.ck,
+            comment: None,
+
+            continent: None,
+            country_prefix: None,
+
+            radio_number: None,
+            station_name: None,
+            net_bios_name: None,
+
+            deleted_at: None,
+        }
+    }
 }
 
 impl From<crate::xml::ContactInfo<'_>> for ContactData {
@@ -83,7 +192,6 @@ impl From<crate::xml::ContactInfo<'_>> for ContactData {
             timestamp: value.timestamp,
 
             mode: value.mode.to_owned(),
-            // band: value.band.to_owned(),
             freq_rx: value.freq_rx,
             freq_tx: value.freq_tx,
 
@@ -106,36 +214,61 @@ impl From<crate::xml::ContactInfo<'_>> for ContactData {
             location_source: LocationSource::NoLocation,
             latitude: None,
             longitude: None,
+
+            // N1MM sends the lower edge of the band in MHz
+            band: adif::Band::from_frequency(value.band.into()).map(Band),
+            log_name: None,
+            contest_id: None,
+
+            grid_square: value.grid_square.map(|s| s.to_owned()),
+            sent_number: value.sent_number.try_into().ok(),
+            recv_number: value.recv_number.try_into().ok(),
+
+            recv_name: value.name.map(|s| s.to_owned()),
+            recv_power: value.power.map(|s| s.to_owned()),
+            recv_qth: value.location.map(|s| s.to_owned()),
+            prec: value.prec.map(|s| s.to_owned()),
+            ck: value.ck.try_into().ok(),
+            comment: value.comment.map(|s| s.to_owned()),
+
+            continent: Some(value.continent.to_owned()).filter(|s| !s.is_empty()),
+            country_prefix: Some(value.country_prefix.to_owned()).filter(|s| !s.is_empty()),
+
+            radio_number: Some(value.radio_number),
+            station_name: Some(value.station_name.to_owned()).filter(|s| !s.is_empty()),
+            net_bios_name: value.net_bios_name.map(|s| s.to_owned()),
+
+            deleted_at: None,
         }
     }
 }
 
-impl From<crate::adif::N1MMAdifRecord<'_>> for ContactData {
+impl From<crate::adif::N1MMAdifRecord> for ContactData {
     fn from(value: crate::adif::N1MMAdifRecord) -> Self {
-        assert_eq!(value.time_on, value.time_off);
+        // `time_off` is only later than `time_on` for long contacts, which are kept at their start
+        let coordinates = value.coordinates();
 
         Self {
-            n1mm_id: Some(value.n1mm_id.to_owned()),
+            n1mm_id: Some(value.n1mm_id),
 
-            recv_callsign: value.recv_callsign.to_owned(),
-            sent_callsign: value.sent_callsign.to_owned(),
+            recv_callsign: value.recv_callsign,
+            sent_callsign: value.sent_callsign,
 
             recv_signal_report: value.recv_signal_report,
             sent_signal_report: value.sent_signal_report,
             timestamp: value.qso_date.with_time(value.time_on),
 
-            mode: value.mode.to_owned(),
-            // band: value.band.to_owned(),
+            mode: value.mode.to_string(),
             freq_rx: (value.freq_rx * 1000000.0).round() as i64,
             freq_tx: (value.freq_tx * 1000000.0).round() as i64,
 
-            exchange1: value.n1mm_exchange1.map(|s| s.to_owned()),
-            section: value.section.map(|s| s.to_owned()),
-            prefix_wpx: value.prefix_wpx.map(|s| s.to_owned()),
+            exchange1: value.n1mm_exchange1,
+            section: value.section,
+            prefix_wpx: value.prefix_wpx,
             cq_zone: value.cq_zone.into(),
 
-            contest_name: value.contest_name.map(|s| s.to_owned()),
-            operator: value.operator.map(|s| s.to_owned()),
+            contest_name: value.contest_name,
+            operator: value.operator,
 
             is_mult_1: value.n1mm_is_mult_1,
             is_mult_2: value.n1mm_is_mult_2,
@@ -145,19 +278,154 @@ impl From<crate::adif::N1MMAdifRecord<'_>> for ContactData {
             is_claimed_qso: value.n1mm_is_claimed_qso,
             points: value.n1mm_points,
 
+            location_source: match coordinates {
+                Some(_) => LocationSource::Log,
+                None => LocationSource::NoLocation,
+            },
+            latitude: coordinates.map(|c| c.0 as f32),
+            longitude: coordinates.map(|c| c.1 as f32),
+
+            band: value.band.is_known().then_some(Band(value.band)),
+            log_name: None,
+            contest_id: None,
+
+            grid_square: value.gridsquare.map(|g| g.to_string()),
+            sent_number: value.serial_number_tx.try_into().ok(),
+            recv_number: value.serial_number_rx.and_then(|n| n.try_into().ok()),
+
+            recv_name: value.name,
+            recv_power: value.rx_power,
+            recv_qth: value.qth,
+            prec: value.precedence,
+            ck: value.check.and_then(|c| c.parse().ok()),
+            comment: value.comment,
+
+            continent: value
+                .n1mm_continent
+                .is_known()
+                .then(|| value.n1mm_continent.to_string()),
+            country_prefix: None,
+
+            radio_number: Some(value.n1mm_radio_number),
+            station_name: None,
+            net_bios_name: Some(value.n1mm_netbios_name).filter(|s| !s.is_empty()),
+
+            deleted_at: None,
+        }
+    }
+}
+
+impl From<crate::n1mm::LogEntry> for ContactData {
+    fn from(value: crate::n1mm::LogEntry) -> Self {
+        Self {
+            n1mm_id: Some(value.id),
+
+            recv_callsign: value.recv_callsign,
+            sent_callsign: value.contest.callsign.unwrap_or_default(),
+
+            recv_signal_report: value.recv_signal_report,
+            sent_signal_report: value.sent_signal_report,
+            timestamp: value.timestamp,
+
+            mode: value.mode,
+            freq_rx: value.freq_rx,
+            freq_tx: value.freq_tx,
+
+            exchange1: value.exchange1,
+            section: value.section,
+            prefix_wpx: value.prefix_wpx,
+            cq_zone: value.cq_zone,
+
+            contest_name: Some(value.contest.name),
+            operator: value.operator,
+
+            is_mult_1: value.is_mult_1,
+            is_mult_2: value.is_mult_2,
+            is_mult_3: value.is_mult_3,
+
+            is_run_qso: value.is_run_qso,
+            is_claimed_qso: value.is_claimed_qso,
+            points: value.points,
+
             location_source: LocationSource::NoLocation,
             latitude: None,
             longitude: None,
+
+            band: adif::Band::from_frequency(value.band).map(Band),
+            log_name: None,
+            contest_id: None,
+
+            grid_square: value.grid_square,
+            sent_number: value.sent_number,
+            recv_number: value.recv_number,
+
+            recv_name: value.recv_name,
+            recv_power: value.recv_power,
+            recv_qth: value.recv_qth,
+            prec: value.prec,
+            ck: value.ck,
+            comment: value.comment,
+
+            continent: value.continent,
+            country_prefix: value.country_prefix,
+
+            radio_number: value.radio_number,
+            station_name: value.station_name,
+            net_bios_name: value.net_bios_name,
+
+            deleted_at: None,
         }
     }
 }
 
-#[derive(Debug, Clone, diesel::AsExpression, diesel::FromSqlRow, Serialize, Deserialize)]
+/// ADIF band, stored as its name
+#[derive(
+    Debug, Clone, PartialEq, diesel::AsExpression, diesel::FromSqlRow, Serialize, Deserialize,
+)]
+#[diesel(sql_type = diesel::sql_types::VarChar)]
+#[serde(transparent)]
+pub struct Band(pub adif::Band);
+
+impl<DB: diesel::backend::Backend> FromSql<diesel::sql_types::VarChar, DB> for Band
+where
+    String: FromSql<diesel::sql_types::VarChar, DB>,
+{
+    fn from_sql(
+        bytes: <DB as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        Ok(Band(String::from_sql(bytes)?.into()))
+    }
+}
+
+impl<DB: diesel::backend::Backend> ToSql<diesel::sql_types::VarChar, DB> for Band
+where
+    str: ToSql<diesel::sql_types::VarChar, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        self.0.as_str().to_sql(out)
+    }
+}
+
+async_graphql::scalar!(Band);
+
+#[derive(
+    Debug, Clone, PartialEq, diesel::AsExpression, diesel::FromSqlRow, Serialize, Deserialize,
+)]
 #[diesel(sql_type = diesel::sql_types::VarChar)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum LocationSource {
     NoLocation,
     Prefix,
     HamQTH,
+    /// The QRZ.com XML API
+    QRZ,
+    /// callook.info, US callsigns only
+    Callook,
+    /// Coordinates from the imported log
+    Log,
 }
 
 impl<DB: diesel::backend::Backend> FromSql<diesel::sql_types::VarChar, DB> for LocationSource
@@ -171,7 +439,10 @@ where
             "NoLocation" => Ok(LocationSource::NoLocation),
             "Prefix" => Ok(LocationSource::Prefix),
             "HamQTH" => Ok(LocationSource::HamQTH),
-            s => todo!(),
+            "QRZ" => Ok(LocationSource::QRZ),
+            "Callook" => Ok(LocationSource::Callook),
+            "Log" => Ok(LocationSource::Log),
+            s => Err(format!("Unknown location source `{}`", s).into()),
         }
     }
 }
@@ -188,6 +459,9 @@ where
             LocationSource::NoLocation => "NoLocation".to_sql(out),
             LocationSource::Prefix => "Prefix".to_sql(out),
             LocationSource::HamQTH => "HamQTH".to_sql(out),
+            LocationSource::QRZ => "QRZ".to_sql(out),
+            LocationSource::Callook => "Callook".to_sql(out),
+            LocationSource::Log => "Log".to_sql(out),
         }
     }
 }
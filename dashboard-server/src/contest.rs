@@ -0,0 +1,38 @@
+/// A contest N1MM has logged, live contacts are grouped by it
+#[derive(Debug, Clone, async_graphql::SimpleObject, diesel::Queryable, diesel::Selectable)]
+#[graphql(complex)]
+#[diesel(table_name = crate::schema::contests)]
+#[diesel(check_for_backend(crate::database::DbBackend))]
+pub struct Contest {
+    pub id: i32,
+
+    /// N1MM's `contestnr`, 0 for contests only known by name from an ADIF file
+    pub contest_number: i32,
+    pub contest_name: String,
+    /// The N1MM database the contest is logged in
+    pub db_name: Option<String>,
+
+    #[graphql(skip)]
+    pub first_seen: time::PrimitiveDateTime,
+    #[graphql(skip)]
+    pub last_seen: time::PrimitiveDateTime,
+}
+
+#[async_graphql::ComplexObject]
+impl Contest {
+    async fn first_seen(&self) -> String {
+        format_timestamp(self.first_seen)
+    }
+
+    async fn last_seen(&self) -> String {
+        format_timestamp(self.last_seen)
+    }
+}
+
+fn format_timestamp(timestamp: time::PrimitiveDateTime) -> String {
+    timestamp
+        .format(time::macros::format_description!(
+            "[year]-[month]-[day]T[hour]:[minute]:[second]Z"
+        ))
+        .unwrap()
+}
@@ -0,0 +1,1171 @@
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use diesel::{connection::SimpleConnection, migration::MigrationSource, prelude::*, r2d2};
+use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
+
+use crate::{
+    activity,
+    contact_data::{self, ContactData},
+    contest::Contest,
+    location,
+    location_cache::{self, CallsignLocation},
+    lookup_queue::{self, Lookup},
+    prefix,
+    profile::Profile,
+    revision::{Action, Origin, Revision},
+    schema::{contact_revisions, contacts, location_lookups},
+};
+
+#[cfg(test)]
+mod test;
+
+/// Every migration in `migrations/`, applied when the database is opened
+#[cfg(not(feature = "postgres"))]
+pub const MIGRATIONS: EmbeddedMigrations = diesel_migrations::embed_migrations!("migrations");
+/// Every migration in `migrations-postgres/`, applied when the database is opened
+#[cfg(feature = "postgres")]
+pub const MIGRATIONS: EmbeddedMigrations =
+    diesel_migrations::embed_migrations!("migrations-postgres");
+
+/// The backend contacts are stored in, SQLite unless the `postgres` feature is enabled
+#[cfg(not(feature = "postgres"))]
+pub type DbBackend = diesel::sqlite::Sqlite;
+#[cfg(not(feature = "postgres"))]
+pub type DbConnection = SqliteConnection;
+#[cfg(feature = "postgres")]
+pub type DbBackend = diesel::pg::Pg;
+#[cfg(feature = "postgres")]
+pub type DbConnection = PgConnection;
+
+/// Connections to read with, WAL mode lets them run alongside the writer
+const READERS: u32 = 4;
+
+type Pool = r2d2::Pool<r2d2::ConnectionManager<DbConnection>>;
+
+/// The contact log, queries run on tokio's blocking threads so a slow one doesn't hold up the
+/// others
+#[derive(Clone)]
+pub struct Database {
+    /// A single connection, SQLite only allows one writer at a time
+    writer: Pool,
+    readers: Pool,
+    last: Arc<tokio::sync::RwLock<Vec<LastData>>>,
+}
+
+/// Which contacts a query covers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Scope {
+    /// The live contacts of the contest N1MM most recently logged
+    #[default]
+    Current,
+    /// The live contacts of a contest by its ID
+    Contest(i32),
+    /// An imported log
+    Log(String),
+}
+
+/// The most recent contact of a scope, sent to those watching it
+struct LastData {
+    scope: Scope,
+    sender: broadcast::Sender<Option<ContactData>>,
+    value: Option<ContactData>,
+}
+
+impl Database {
+    /// Opens the database at `url`, a file for SQLite, creating and migrating it as needed
+    pub fn open(url: &str) -> anyhow::Result<Self> {
+        let writer = pool(url, 1, false)?;
+        migrate(&mut *writer.get()?)?;
+
+        Ok(Self {
+            writer,
+            readers: pool(url, READERS, true)?,
+            last: Arc::new(tokio::sync::RwLock::new(Vec::new())),
+        })
+    }
+
+    /// Runs `f` with a read connection on a blocking thread
+    async fn with_reader<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut DbConnection) -> anyhow::Result<T> + Send + 'static,
+    {
+        let readers = self.readers.clone();
+        tokio::task::spawn_blocking(move || f(&mut *readers.get()?)).await?
+    }
+
+    /// Runs `f` with the write connection on a blocking thread, waiting for other writes
+    async fn with_writer<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut DbConnection) -> anyhow::Result<T> + Send + 'static,
+    {
+        let writer = self.writer.clone();
+        tokio::task::spawn_blocking(move || f(&mut *writer.get()?)).await?
+    }
+
+    pub async fn insert_rows(&self, data: &[ContactData]) -> anyhow::Result<()> {
+        use crate::schema::contacts::dsl::*;
+        let data = data.to_vec();
+        let inserted_count = self
+            .with_writer(move |conn| {
+                Ok(diesel::insert_into(contacts).values(&data).execute(conn)?)
+            })
+            .await?;
+        log::info!("Inserted {} rows", inserted_count);
+        self.maybe_publish(None, true, false).await
+    }
+
+    /// Marks a contact deleted, it is kept for its history and can be restored
+    pub async fn delete_by_id(&self, delete_id: &str, origin: &Origin) -> anyhow::Result<()> {
+        let count = {
+            let (delete_id, origin) = (delete_id.to_owned(), origin.clone());
+            self.with_writer(move |conn| delete(conn, &delete_id, &origin))
+                .await?
+        };
+        log::info!("Deleted {} rows", count);
+
+        if count > 0 {
+            self.maybe_publish(Some(delete_id), true, true).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn update(
+        &self,
+        data: &ContactData,
+        origin: &Origin,
+        publish: bool,
+    ) -> anyhow::Result<(Option<contact_data::LocationSource>)> {
+        self.write(data, origin, None, publish).await
+    }
+
+    /// Sets a contact back to a revision, undeleting it if it was deleted
+    pub async fn restore(&self, revision_id: i32) -> anyhow::Result<ContactData> {
+        use crate::schema::contacts::dsl::*;
+        let revision: Revision = self
+            .with_reader(move |conn| Ok(contact_revisions::table.find(revision_id).first(conn)?))
+            .await?;
+        let data = revision.parse_snapshot()?;
+
+        let old_source = self
+            .write(&data, &Origin::default(), Some(Action::Restore), true)
+            .await?;
+        if old_source.is_none() {
+            // the location of a different callsign doesn't apply
+            self.get_location_from_prefix(&data).await?;
+        }
+        log::info!("Restored {:?} to revision {}", data.id(), revision_id);
+
+        let restored_id = data.id().map(str::to_owned);
+        self.with_reader(move |conn| Ok(contacts.filter(id.eq(restored_id)).first(conn)?))
+            .await
+    }
+
+    /// Every change to a contact, oldest first
+    pub async fn history(&self, contact: &str) -> anyhow::Result<Vec<Revision>> {
+        use crate::schema::contact_revisions::dsl::*;
+        let contact = contact.to_owned();
+        self.with_reader(move |conn| {
+            Ok(contact_revisions
+                .filter(contact_id.eq(contact))
+                .order(id.asc())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    /// Deleted contacts, the most recently deleted first
+    pub async fn deleted(&self, scope: &Scope) -> anyhow::Result<Vec<ContactData>> {
+        use crate::schema::contacts::dsl::*;
+        let scope = scope.clone();
+        self.with_reader(move |conn| {
+            let expr = filter_scope(conn, contacts.into_boxed(), &scope)?;
+
+            Ok(expr
+                .filter(deleted_at.is_not_null())
+                .order(deleted_at.desc())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    /// Inserts or replaces a contact and records the change, `action` is worked out if not given
+    async fn write(
+        &self,
+        data: &ContactData,
+        origin: &Origin,
+        action: Option<Action>,
+        publish: bool,
+    ) -> anyhow::Result<(Option<contact_data::LocationSource>)> {
+        let (data, origin) = (data.clone(), origin.clone());
+        let (data, location_source_info, count) = self
+            .with_writer(move |conn| {
+                conn.transaction(|conn| {
+                    let (data, location_source_info, count) = write(conn, &data, &origin, action)?;
+                    Ok((data.into_owned(), location_source_info, count))
+                })
+            })
+            .await?;
+
+        if publish {
+            log::info!("Updated {} rows", count);
+        }
+
+        if publish && count > 0 {
+            self.maybe_publish(data.id(), true, true).await?;
+        }
+
+        Ok(location_source_info)
+    }
+
+    pub async fn add_location(
+        &self,
+        update_id: &str,
+        source: contact_data::LocationSource,
+        lat: f32,
+        lng: f32,
+    ) -> anyhow::Result<()> {
+        use crate::schema::contacts::dsl::*;
+        let owned_id = update_id.to_owned();
+        self.with_writer(move |conn| {
+            let update = diesel::update(contacts.filter(id.eq(owned_id))).set((
+                location_source.eq(source),
+                latitude.eq(lat),
+                longitude.eq(lng),
+            ));
+            Ok(update.execute(conn)?)
+        })
+        .await?;
+        log::info!("Added location to {}", update_id);
+        self.maybe_publish(Some(update_id), false, true).await
+    }
+
+    pub async fn update_and_fetch_location(
+        &self,
+        providers: &location::Chain,
+        data: &ContactData,
+        origin: &Origin,
+        publish: bool,
+    ) -> anyhow::Result<()> {
+        let old_source = self.update(data, origin, false).await?;
+
+        // coordinates from an imported log don't need a lookup
+        if let (contact_data::LocationSource::Log, Some(lat), Some(lng)) =
+            (&data.location_source, data.latitude, data.longitude)
+        {
+            if old_source.is_some() {
+                self.add_location(
+                    data.id().unwrap(),
+                    contact_data::LocationSource::Log,
+                    lat,
+                    lng,
+                )
+                .await?;
+            }
+            return Ok(());
+        }
+
+        match old_source {
+            Some(contact_data::LocationSource::NoLocation) => {
+                self.get_location_from_prefix(data).await
+            }
+            Some(contact_data::LocationSource::Prefix) => Ok(()),
+            Some(contact_data::LocationSource::HamQTH) => Ok(()),
+            Some(contact_data::LocationSource::QRZ) => Ok(()),
+            Some(contact_data::LocationSource::Callook) => Ok(()),
+            Some(contact_data::LocationSource::Log) => Ok(()),
+            None => self.get_location(data, providers).await,
+        }
+    }
+
+    async fn get_location(
+        &self,
+        data: &ContactData,
+        providers: &location::Chain,
+    ) -> anyhow::Result<()> {
+        // another contact with the station may have looked it up already
+        if let Some(cached) = self.cached_location(&data.recv_callsign).await? {
+            log::debug!("Cached location {:?} for {}", cached, data.recv_callsign);
+            return match cached.coordinates() {
+                Some((lat, lng)) => {
+                    self.add_location(data.id().unwrap(), cached.location_source, lat, lng)
+                        .await
+                }
+                None => self.get_location_from_prefix(data).await,
+            };
+        }
+
+        match self.get_location_from_providers(data, providers).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                log::debug!("Failing over to using prefix for {}", data.recv_callsign);
+                self.get_location_from_prefix(data).await
+            }
+            Err(e) => {
+                // try again once the providers can be reached
+                log::debug!("Queuing a lookup of {}: {}", data.recv_callsign, e);
+                self.get_location_from_prefix(data).await?;
+                self.queue_lookup(data.id().unwrap()).await
+            }
+        }
+    }
+
+    /// Looks up the location of a queued contact again, an error if it should be retried later
+    pub async fn enrich(
+        &self,
+        contact_id: &str,
+        providers: &location::Chain,
+    ) -> anyhow::Result<()> {
+        use crate::schema::contacts::dsl::*;
+        let owned_id = contact_id.to_owned();
+        let contact: Option<ContactData> = self
+            .with_reader(move |conn| {
+                Ok(contacts
+                    .filter(id.eq(owned_id))
+                    .filter(deleted_at.is_null())
+                    .first(conn)
+                    .optional()?)
+            })
+            .await?;
+
+        // deleted, or located some other way since it was queued
+        let Some(contact) = contact.filter(|c| {
+            matches!(
+                c.location_source,
+                contact_data::LocationSource::Prefix | contact_data::LocationSource::NoLocation
+            )
+        }) else {
+            return Ok(());
+        };
+
+        if let Some(cached) = self.cached_location(&contact.recv_callsign).await? {
+            if let Some((lat, lng)) = cached.coordinates() {
+                self.add_location(contact_id, cached.location_source, lat, lng)
+                    .await?;
+            }
+            return Ok(());
+        }
+        self.get_location_from_providers(&contact, providers)
+            .await
+            .map(drop)
+    }
+
+    async fn get_location_from_providers(
+        &self,
+        data: &ContactData,
+        providers: &location::Chain,
+    ) -> anyhow::Result<bool> {
+        if providers.is_empty() {
+            return Ok(false);
+        }
+
+        log::debug!("Fetching location for {}", data.recv_callsign);
+        let mut location = providers.locate(&data.recv_callsign).await?;
+        // the operator is worth keeping even without coordinates
+        if let Some(profile) = location.as_mut().and_then(|l| l.profile.take()) {
+            self.store_profile(profile).await?;
+        }
+        let location = location.and_then(|l| Some((l.source, l.coordinates?)));
+        self.cache_location(CallsignLocation::new(
+            &data.recv_callsign,
+            location
+                .as_ref()
+                .map_or(contact_data::LocationSource::NoLocation, |l| l.0.clone()),
+            location.as_ref().map(|l| l.1),
+            now(),
+        ))
+        .await?;
+
+        match location {
+            Some((source, (lat, lng))) => {
+                log::info!("Location {:?} for {}", (lat, lng), data.recv_callsign);
+                self.add_location(data.id().unwrap(), source, lat, lng)
+                    .await?;
+                Ok(true)
+            }
+            None => {
+                log::info!("No location for {}", data.recv_callsign);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn get_location_from_prefix(&self, data: &ContactData) -> anyhow::Result<()> {
+        let location = prefix::get_location_for_callsign(&data.recv_callsign);
+        if let Some(l) = location {
+            self.add_location(
+                data.id().unwrap(),
+                contact_data::LocationSource::Prefix,
+                l.0,
+                l.1,
+            )
+            .await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The location of a callsign from an earlier lookup, unless it has expired
+    pub async fn cached_location(
+        &self,
+        callsign: &str,
+    ) -> anyhow::Result<Option<CallsignLocation>> {
+        let callsign = callsign.to_owned();
+        self.with_reader(move |conn| Ok(cached_location(conn, &callsign, now())?))
+            .await
+    }
+
+    /// Saves a lookup for the next contact with the callsign, replacing any earlier one
+    pub async fn cache_location(&self, location: CallsignLocation) -> anyhow::Result<()> {
+        self.with_writer(move |conn| Ok(cache_location(conn, &location)?))
+            .await
+    }
+
+    /// What is known about the operator of a callsign
+    pub async fn profile(&self, callsign: &str) -> anyhow::Result<Option<Profile>> {
+        let callsign = callsign.to_owned();
+        self.with_reader(move |conn| Ok(profile(conn, &callsign)?))
+            .await
+    }
+
+    /// Replaces the profile of the callsign
+    pub async fn store_profile(&self, profile: Profile) -> anyhow::Result<()> {
+        self.with_writer(move |conn| Ok(store_profile(conn, &profile)?))
+            .await
+    }
+
+    /// Queues another lookup of a contact's location, unless one is queued already
+    pub async fn queue_lookup(&self, contact_id: &str) -> anyhow::Result<()> {
+        let contact_id = contact_id.to_owned();
+        self.with_writer(move |conn| Ok(queue_lookup(conn, &contact_id, now())?))
+            .await?;
+        Ok(())
+    }
+
+    /// Queues a lookup of every live contact located by prefix, returning how many were queued
+    pub async fn queue_prefix_contacts(&self) -> anyhow::Result<usize> {
+        self.with_writer(move |conn| Ok(queue_prefix_contacts(conn, now())?))
+            .await
+    }
+
+    /// Queued lookups that are due, the longest waiting first
+    pub async fn due_lookups(&self, limit: i64) -> anyhow::Result<Vec<Lookup>> {
+        self.with_reader(move |conn| Ok(due_lookups(conn, now(), limit)?))
+            .await
+    }
+
+    pub async fn finish_lookup(&self, contact_id: &str) -> anyhow::Result<()> {
+        use crate::schema::location_lookups::dsl;
+        let contact_id = contact_id.to_owned();
+        self.with_writer(move |conn| {
+            Ok(diesel::delete(dsl::location_lookups.find(contact_id)).execute(conn)?)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Puts off a lookup that failed again, longer each time
+    pub async fn lookup_failed(&self, lookup: &Lookup, error: &str) -> anyhow::Result<()> {
+        let (lookup, error) = (lookup.clone(), error.to_owned());
+        self.with_writer(move |conn| Ok(lookup_failed(conn, &lookup, &error, now())?))
+            .await?;
+        Ok(())
+    }
+
+    /// Makes every queued lookup due now
+    pub async fn retry_lookups(&self) -> anyhow::Result<()> {
+        self.with_writer(move |conn| Ok(retry_lookups(conn, now())?))
+            .await?;
+        Ok(())
+    }
+
+    /// How many contacts are waiting for a lookup
+    pub async fn queued_lookups(&self) -> anyhow::Result<i64> {
+        use crate::schema::location_lookups::dsl::*;
+        self.with_reader(move |conn| Ok(location_lookups.count().get_result(conn)?))
+            .await
+    }
+
+    pub async fn contacts(&self, scope: &Scope) -> anyhow::Result<Vec<ContactData>> {
+        use crate::schema::contacts::dsl::*;
+        let scope = scope.clone();
+        self.with_reader(move |conn| {
+            let expr = live(conn, &scope)?;
+
+            Ok(expr.order(timestamp.asc()).load(conn)?)
+        })
+        .await
+    }
+
+    pub async fn most_recent(
+        &self,
+        scope: &Scope,
+        count: Option<u32>,
+        is_run: Option<bool>,
+        op: Option<String>,
+    ) -> anyhow::Result<Vec<ContactData>> {
+        use crate::schema::contacts::dsl::*;
+        let scope = scope.clone();
+        self.with_reader(move |conn| {
+            let mut expr = live(conn, &scope)?;
+
+            if let Some(is_run) = is_run {
+                expr = expr.filter(is_run_qso.eq(is_run));
+            }
+
+            if let Some(op) = op {
+                expr = expr.filter(operator.eq(op));
+            }
+
+            expr = expr.order(timestamp.desc());
+
+            if let Some(c) = count {
+                expr = expr.limit(c.into());
+            }
+
+            Ok(expr.load(conn)?)
+        })
+        .await
+    }
+
+    pub async fn count(
+        &self,
+        scope: &Scope,
+        is_run: Option<bool>,
+        op: Option<String>,
+    ) -> anyhow::Result<u64> {
+        use crate::schema::contacts::dsl::*;
+        let scope = scope.clone();
+        self.with_reader(move |conn| {
+            let mut expr = live(conn, &scope)?;
+
+            if let Some(is_run) = is_run {
+                expr = expr.filter(is_run_qso.eq(is_run));
+            }
+
+            if let Some(op) = op {
+                expr = expr.filter(operator.eq(op));
+            }
+
+            let count: i64 = expr.count().first(conn)?;
+            Ok(count.try_into()?)
+        })
+        .await
+    }
+
+    /// The ID of the contest of an N1MM packet, which becomes the current contest
+    pub async fn seen_contest(
+        &self,
+        number: i32,
+        name: &str,
+        database: Option<&str>,
+    ) -> anyhow::Result<i32> {
+        let (name, database) = (name.to_owned(), database.map(str::to_owned));
+        self.with_writer(move |conn| {
+            Ok(conn.transaction(|conn| seen_contest(conn, number, &name, database.as_deref()))?)
+        })
+        .await
+    }
+
+    /// Every contest, the current one first
+    pub async fn contests(&self) -> anyhow::Result<Vec<Contest>> {
+        use crate::schema::contests::dsl::*;
+        self.with_reader(|conn| Ok(contests.order(last_seen.desc()).load(conn)?))
+            .await
+    }
+
+    pub async fn contest(&self, scope: &Scope) -> anyhow::Result<Option<Contest>> {
+        use crate::schema::contests::dsl::*;
+        let scope = scope.clone();
+        self.with_reader(move |conn| {
+            let contest_id = match scope {
+                Scope::Current => current_contest(conn)?,
+                Scope::Contest(contest) => Some(contest),
+                Scope::Log(_) => None,
+            };
+            match contest_id {
+                Some(contest) => Ok(contests.find(contest).first(conn).optional()?),
+                None => Ok(None),
+            }
+        })
+        .await
+    }
+
+    /// Names of the imported logs
+    pub async fn logs(&self) -> anyhow::Result<Vec<String>> {
+        use crate::schema::contacts::dsl::*;
+        let names: Vec<Option<String>> = self
+            .with_reader(|conn| {
+                Ok(contacts
+                    .select(log_name)
+                    .filter(log_name.is_not_null())
+                    .filter(deleted_at.is_null())
+                    .distinct()
+                    .order(log_name.asc())
+                    .load(conn)?)
+            })
+            .await?;
+        Ok(names.into_iter().flatten().collect())
+    }
+
+    /// Replaces the contacts of an imported log, published to those watching it
+    pub async fn import_log(&self, name: &str, data: &[ContactData]) -> anyhow::Result<()> {
+        use crate::schema::contacts::dsl::*;
+        let (owned_name, data) = (name.to_owned(), data.to_vec());
+
+        let (deleted, inserted) = self
+            .with_writer(move |conn| {
+                Ok(conn.transaction(|conn| {
+                    let deleted =
+                        diesel::delete(contacts.filter(log_name.eq(&owned_name))).execute(conn)?;
+                    let inserted = diesel::insert_into(contacts).values(&data).execute(conn)?;
+                    diesel::QueryResult::Ok((deleted, inserted))
+                })?)
+            })
+            .await?;
+        log::info!(
+            "Imported {} rows into log {}, replacing {}",
+            inserted,
+            name,
+            deleted
+        );
+        self.maybe_publish(None, true, false).await
+    }
+
+    /// The most recent contact of `scope` each time it changes
+    pub async fn watch_latest(
+        &self,
+        scope: &Scope,
+    ) -> tokio::sync::broadcast::Receiver<Option<ContactData>> {
+        let mut last = self.last.write().await;
+        if let Some(watched) = last.iter().find(|l| l.scope == *scope) {
+            return watched.sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(8);
+        last.push(LastData {
+            scope: scope.clone(),
+            sender,
+            value: None,
+        });
+        receiver
+    }
+
+    async fn maybe_publish(
+        &self,
+        updated_id: Option<&str>,
+        could_change_first: bool,
+        could_update_value: bool,
+    ) -> anyhow::Result<()> {
+        let mut last = self.last.write().await;
+        last.retain(|l| l.sender.receiver_count() > 0);
+
+        for watched in last.iter_mut() {
+            if !could_change_first {
+                match (updated_id, watched.value.as_ref()) {
+                    (Some(s), Some(l)) => {
+                        if l.id().is_some_and(|l| s != l) {
+                            // just an update to something not at the front
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let most_recent = self
+                .most_recent(&watched.scope, Some(1), None, None)
+                .await?
+                .pop();
+
+            match (watched.value.as_ref(), &most_recent) {
+                (None, None) => {
+                    continue;
+                }
+                (Some(last_contact), Some(most_recent)) => {
+                    if !could_update_value && last_contact.id() == most_recent.id() {
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+
+            log::info!(
+                "Sending update to {} subscribers of {:?}: {:?}",
+                watched.sender.receiver_count(),
+                watched.scope,
+                most_recent.as_ref().map(|d| (&d.recv_callsign, d.id()))
+            );
+            watched.value = most_recent.clone();
+            let _ = watched.sender.send(most_recent);
+        }
+
+        Ok(())
+    }
+}
+
+/// Connections to the database, readers can't write
+fn pool(url: &str, size: u32, read_only: bool) -> anyhow::Result<Pool> {
+    Ok(r2d2::Pool::builder()
+        .test_on_check_out(true)
+        .max_size(size)
+        .connection_customizer(Box::new(Setup { read_only }))
+        .build(r2d2::ConnectionManager::new(url))?)
+}
+
+#[derive(Debug)]
+struct Setup {
+    read_only: bool,
+}
+
+impl r2d2::CustomizeConnection<DbConnection, r2d2::Error> for Setup {
+    fn on_acquire(&self, conn: &mut DbConnection) -> Result<(), r2d2::Error> {
+        // wait for the writer rather than failing with SQLITE_BUSY
+        #[cfg(not(feature = "postgres"))]
+        let setup = if self.read_only {
+            "PRAGMA busy_timeout = 5000; PRAGMA query_only = ON;"
+        } else {
+            "PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;"
+        };
+        #[cfg(feature = "postgres")]
+        let setup = if self.read_only {
+            "SET default_transaction_read_only = on;"
+        } else {
+            return Ok(());
+        };
+        conn.batch_execute(setup).map_err(r2d2::Error::QueryError)
+    }
+}
+
+/// Brings the schema up to date, refusing a database with migrations from a newer version
+pub fn migrate(conn: &mut DbConnection) -> anyhow::Result<()> {
+    let migrations =
+        MigrationSource::<DbBackend>::migrations(&MIGRATIONS).map_err(|e| anyhow::anyhow!(e))?;
+    let known: Vec<_> = migrations.iter().map(|m| m.name().version()).collect();
+
+    let applied = conn.applied_migrations().map_err(|e| anyhow::anyhow!(e))?;
+    if let Some(unknown) = applied.iter().find(|v| !known.contains(v)) {
+        return Err(anyhow::anyhow!(
+            "The database has migration {} which this version does not know, \
+             it was last used by a newer dashboard-server",
+            unknown
+        ));
+    }
+
+    for version in conn
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!(e))?
+    {
+        log::info!("Applied migration {}", version);
+    }
+    Ok(())
+}
+
+/// Inserts or replaces a contact and records it, with its old location source if the callsign
+/// is the same
+fn write<'a>(
+    conn: &mut DbConnection,
+    data: &'a ContactData,
+    origin: &Origin,
+    action: Option<Action>,
+) -> anyhow::Result<(
+    std::borrow::Cow<'a, ContactData>,
+    Option<contact_data::LocationSource>,
+    usize,
+)> {
+    use crate::schema::contacts::dsl::*;
+
+    type Old = (
+        String,
+        contact_data::LocationSource,
+        Option<i32>,
+        Option<String>,
+        Option<String>,
+    );
+    let old: Option<Old> = contacts
+        .select((
+            recv_callsign,
+            location_source,
+            contest_id,
+            station_name,
+            country_prefix,
+        ))
+        .filter(id.eq(data.id()))
+        .load(conn)?
+        .pop();
+    let existed = old.is_some();
+
+    // contacts from an ADIF file only know their contest by name, those already logged stay in
+    // the contest N1MM logged them in
+    let mut data = std::borrow::Cow::Borrowed(data);
+    if data.contest_id.is_none() && data.log_name.is_none() {
+        data.to_mut().contest_id = match old.as_ref().and_then(|(_, _, contest, ..)| *contest) {
+            Some(contest) => Some(contest),
+            None => Some(contest_named(
+                conn,
+                data.contest_name.as_deref(),
+                data.timestamp,
+            )?),
+        };
+    }
+
+    // an ADIF file has no station name or country prefix, keep what N1MM sent over UDP
+    if let Some((_, _, _, old_station, old_prefix)) = &old {
+        if data.station_name.is_none() && old_station.is_some() {
+            data.to_mut().station_name = old_station.clone();
+        }
+        if data.country_prefix.is_none() && old_prefix.is_some() {
+            data.to_mut().country_prefix = old_prefix.clone();
+        }
+    }
+
+    let location_source_info = match old {
+        Some((call, source, ..)) => {
+            if call == data.recv_callsign {
+                Some(source)
+            } else {
+                None
+            }
+        }
+        None => None,
+    };
+
+    let count = if location_source_info.is_some() {
+        diesel::update(contacts.filter(id.eq(data.id())))
+            .set((
+                recv_callsign.eq(&data.recv_callsign),
+                sent_callsign.eq(&data.sent_callsign),
+                recv_signal_report.eq(&data.recv_signal_report),
+                sent_signal_report.eq(&data.sent_signal_report),
+                timestamp.eq(data.timestamp),
+                mode.eq(&data.mode),
+                freq_rx.eq(data.freq_rx),
+                freq_tx.eq(data.freq_tx),
+                exchange1.eq(&data.exchange1),
+                section.eq(&data.section),
+                prefix_wpx.eq(&data.prefix_wpx),
+                operator.eq(&data.operator),
+                contest_name.eq(&data.contest_name),
+                is_mult_1.eq(data.is_mult_1),
+                is_mult_2.eq(data.is_mult_2),
+                is_mult_3.eq(data.is_mult_3),
+                is_run_qso.eq(data.is_run_qso),
+                is_claimed_qso.eq(data.is_claimed_qso),
+                points.eq(data.points),
+                band.eq(&data.band),
+                contest_id.eq(data.contest_id),
+                grid_square.eq(&data.grid_square),
+                sent_number.eq(data.sent_number),
+                recv_number.eq(data.recv_number),
+                recv_name.eq(&data.recv_name),
+                recv_power.eq(&data.recv_power),
+                recv_qth.eq(&data.recv_qth),
+                prec.eq(&data.prec),
+                ck.eq(data.ck),
+                comment.eq(&data.comment),
+                continent.eq(&data.continent),
+                country_prefix.eq(&data.country_prefix),
+                radio_number.eq(data.radio_number),
+                station_name.eq(&data.station_name),
+                net_bios_name.eq(&data.net_bios_name),
+                deleted_at.eq(None::<time::PrimitiveDateTime>),
+            ))
+            .execute(conn)?
+    } else {
+        // PostgreSQL has no REPLACE
+        diesel::delete(contacts.filter(id.eq(data.id()))).execute(conn)?;
+        diesel::insert_into(contacts).values(&*data).execute(conn)?
+    };
+
+    let action = action.unwrap_or(if existed {
+        Action::Replace
+    } else {
+        Action::Insert
+    });
+    record(conn, &data, action, origin)?;
+
+    Ok((data, location_source_info, count))
+}
+
+/// Marks a contact deleted and records it, the number of contacts deleted
+fn delete(conn: &mut DbConnection, delete_id: &str, origin: &Origin) -> anyhow::Result<usize> {
+    use crate::schema::contacts::dsl::*;
+    conn.transaction(|conn| {
+        let contact: Option<ContactData> = contacts
+            .filter(id.eq(delete_id))
+            .filter(deleted_at.is_null())
+            .first(conn)
+            .optional()?;
+        let Some(contact) = contact else {
+            return Ok(0);
+        };
+
+        let count = diesel::update(contacts.filter(id.eq(delete_id)))
+            .set(deleted_at.eq(now()))
+            .execute(conn)?;
+        record(conn, &contact, Action::Delete, origin)?;
+        Ok(count)
+    })
+}
+
+/// Adds a revision of a contact, unless it replaces the contact with what it already was
+fn record(
+    conn: &mut DbConnection,
+    contact: &ContactData,
+    action: Action,
+    origin: &Origin,
+) -> anyhow::Result<()> {
+    let contact_id = contact
+        .id()
+        .ok_or_else(|| anyhow::anyhow!("Cannot record a contact without an ID"))?;
+    let snapshot = serde_json::to_string(contact)?;
+
+    // reading the same ADIF file again at startup isn't a change
+    if action == Action::Replace {
+        let last: Option<(Action, String)> = contact_revisions::table
+            .select((contact_revisions::action, contact_revisions::snapshot))
+            .filter(contact_revisions::contact_id.eq(contact_id))
+            .order(contact_revisions::id.desc())
+            .first(conn)
+            .optional()?;
+        if last.is_some_and(|(last_action, last_snapshot)| {
+            last_action != Action::Delete && last_snapshot == snapshot
+        }) {
+            return Ok(());
+        }
+    }
+
+    diesel::insert_into(contact_revisions::table)
+        .values((
+            contact_revisions::contact_id.eq(contact_id),
+            contact_revisions::action.eq(action),
+            contact_revisions::recorded_at.eq(now()),
+            contact_revisions::station_name.eq(&origin.station_name),
+            contact_revisions::packet.eq(&origin.packet),
+            contact_revisions::snapshot.eq(&snapshot),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+fn cached_location(
+    conn: &mut DbConnection,
+    callsign: &str,
+    now: time::PrimitiveDateTime,
+) -> QueryResult<Option<CallsignLocation>> {
+    use crate::schema::callsign_locations::dsl;
+    let location: Option<CallsignLocation> = dsl::callsign_locations
+        .find(location_cache::normalize(callsign))
+        .first(conn)
+        .optional()?;
+    Ok(location.filter(|l| l.is_fresh(now)))
+}
+
+fn cache_location(conn: &mut DbConnection, location: &CallsignLocation) -> QueryResult<()> {
+    use crate::schema::callsign_locations::dsl::*;
+    conn.transaction(|conn| {
+        diesel::delete(callsign_locations.find(&location.callsign)).execute(conn)?;
+        diesel::insert_into(callsign_locations)
+            .values(location)
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+fn profile(conn: &mut DbConnection, callsign: &str) -> QueryResult<Option<Profile>> {
+    use crate::schema::callsign_profiles::dsl;
+    dsl::callsign_profiles
+        .find(location_cache::normalize(callsign))
+        .first(conn)
+        .optional()
+}
+
+fn store_profile(conn: &mut DbConnection, profile: &Profile) -> QueryResult<()> {
+    use crate::schema::callsign_profiles::dsl::*;
+    conn.transaction(|conn| {
+        diesel::delete(callsign_profiles.find(&profile.callsign)).execute(conn)?;
+        diesel::insert_into(callsign_profiles)
+            .values(profile)
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+fn queue_lookup(
+    conn: &mut DbConnection,
+    contact_id: &str,
+    now: time::PrimitiveDateTime,
+) -> QueryResult<usize> {
+    diesel::insert_into(location_lookups::table)
+        .values(Lookup::new(contact_id, now))
+        .on_conflict_do_nothing()
+        .execute(conn)
+}
+
+fn queue_prefix_contacts(
+    conn: &mut DbConnection,
+    now: time::PrimitiveDateTime,
+) -> QueryResult<usize> {
+    use crate::schema::contacts::dsl::*;
+    conn.transaction(|conn| {
+        let ids: Vec<Option<String>> = contacts
+            .filter(deleted_at.is_null())
+            .filter(location_source.eq(contact_data::LocationSource::Prefix))
+            .select(id)
+            .load(conn)?;
+
+        let mut count = 0;
+        for contact_id in ids.iter().flatten() {
+            count += queue_lookup(conn, contact_id, now)?;
+        }
+        Ok(count)
+    })
+}
+
+fn due_lookups(
+    conn: &mut DbConnection,
+    now: time::PrimitiveDateTime,
+    limit: i64,
+) -> QueryResult<Vec<Lookup>> {
+    use crate::schema::location_lookups::dsl::*;
+    location_lookups
+        .filter(next_run.le(now))
+        .order(next_run.asc())
+        .limit(limit)
+        .load(conn)
+}
+
+fn lookup_failed(
+    conn: &mut DbConnection,
+    lookup: &Lookup,
+    error: &str,
+    now: time::PrimitiveDateTime,
+) -> QueryResult<usize> {
+    use crate::schema::location_lookups::dsl::*;
+    diesel::update(location_lookups.find(&lookup.contact_id))
+        .set((
+            attempts.eq(lookup.attempts + 1),
+            next_run.eq(now + lookup_queue::delay(lookup.attempts + 1)),
+            last_error.eq(error),
+        ))
+        .execute(conn)
+}
+
+fn retry_lookups(conn: &mut DbConnection, now: time::PrimitiveDateTime) -> QueryResult<usize> {
+    use crate::schema::location_lookups::dsl::*;
+    diesel::update(location_lookups.filter(next_run.gt(now)))
+        .set(next_run.eq(now))
+        .execute(conn)
+}
+
+fn filter_scope<'a>(
+    conn: &mut DbConnection,
+    expr: contacts::BoxedQuery<'a, DbBackend>,
+    scope: &'a Scope,
+) -> anyhow::Result<contacts::BoxedQuery<'a, DbBackend>> {
+    use crate::schema::contacts::dsl::*;
+    let contest = match scope {
+        Scope::Log(log) => return Ok(expr.filter(log_name.eq(log))),
+        Scope::Contest(contest) => Some(*contest),
+        Scope::Current => current_contest(conn)?,
+    };
+
+    let expr = expr.filter(log_name.is_null());
+    Ok(match contest {
+        Some(contest) => expr.filter(contest_id.eq(contest)),
+        None => expr.filter(contest_id.is_null()),
+    })
+}
+
+/// Contacts in `scope` that haven't been deleted
+fn live<'a>(
+    conn: &mut DbConnection,
+    scope: &'a Scope,
+) -> anyhow::Result<contacts::BoxedQuery<'a, DbBackend>> {
+    use crate::schema::contacts::dsl::*;
+    Ok(filter_scope(conn, contacts.into_boxed(), scope)?.filter(deleted_at.is_null()))
+}
+
+/// The contest N1MM most recently sent a packet for
+fn current_contest(conn: &mut DbConnection) -> QueryResult<Option<i32>> {
+    use crate::schema::contests::dsl::*;
+    contests
+        .select(id)
+        .order(last_seen.desc())
+        .first(conn)
+        .optional()
+}
+
+/// The most recent contest with a name, or the unnamed contest for contacts without one, added
+/// as last seen at `seen` so a contest only known from ADIF doesn't become the current one
+fn contest_named(
+    conn: &mut DbConnection,
+    name: Option<&str>,
+    seen: time::PrimitiveDateTime,
+) -> QueryResult<i32> {
+    use crate::schema::contests::dsl::*;
+    let name = name.unwrap_or_default();
+
+    let existing = contests
+        .select(id)
+        .filter(contest_name.eq(name))
+        .order(last_seen.desc())
+        .first(conn)
+        .optional()?;
+    if let Some(existing) = existing {
+        return Ok(existing);
+    }
+
+    diesel::insert_into(contests)
+        .values((
+            contest_number.eq(0),
+            contest_name.eq(name),
+            first_seen.eq(seen),
+            last_seen.eq(seen),
+        ))
+        .execute(conn)?;
+    contests
+        .select(id)
+        .filter(contest_number.eq(0).and(contest_name.eq(name)))
+        .first(conn)
+}
+
+fn seen_contest(
+    conn: &mut DbConnection,
+    number: i32,
+    name: &str,
+    database: Option<&str>,
+) -> QueryResult<i32> {
+    use crate::schema::contests::dsl::*;
+    let now = now();
+
+    diesel::insert_into(contests)
+        .values((
+            contest_number.eq(number),
+            contest_name.eq(name),
+            db_name.eq(database),
+            first_seen.eq(now),
+            last_seen.eq(now),
+        ))
+        .on_conflict((contest_number, contest_name))
+        .do_update()
+        .set(last_seen.eq(now))
+        .execute(conn)?;
+
+    let contest = || contests.filter(contest_number.eq(number).and(contest_name.eq(name)));
+    if let Some(database) = database {
+        diesel::update(contest())
+            .set(db_name.eq(database))
+            .execute(conn)?;
+    }
+    contest().select(id).first(conn)
+}
+
+/// The current UTC time, as timestamps are stored
+pub fn now() -> time::PrimitiveDateTime {
+    let now = time::OffsetDateTime::now_utc();
+    time::PrimitiveDateTime::new(now.date(), now.time())
+}
@@ -0,0 +1,498 @@
+use diesel::{prelude::*, sql_query};
+use diesel_migrations::MigrationHarness;
+
+use super::{
+    cache_location, cached_location, contest_named, current_contest, delete, due_lookups, live,
+    lookup_failed, migrate, pool, profile, queue_lookup, queue_prefix_contacts, retry_lookups,
+    seen_contest, store_profile, write, Database, DbConnection, Scope, MIGRATIONS,
+};
+use crate::{
+    contact_data::{ContactData, LocationSource},
+    location_cache::CallsignLocation,
+    lookup_queue,
+    profile::Profile,
+    revision::{Action, Origin, Revision},
+};
+
+#[cfg(not(feature = "postgres"))]
+fn connection() -> DbConnection {
+    SqliteConnection::establish(":memory:").unwrap()
+}
+
+/// A connection to `TEST_DATABASE_URL` that never commits, so tests can share the database
+#[cfg(feature = "postgres")]
+fn connection() -> DbConnection {
+    let url = std::env::var("TEST_DATABASE_URL")
+        .expect("TEST_DATABASE_URL must be set to a scratch PostgreSQL database");
+    let mut conn = PgConnection::establish(&url).unwrap();
+    conn.begin_test_transaction().unwrap();
+    conn
+}
+
+#[test]
+fn fresh_database() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    sql_query("SELECT id, band, log_name, contest_id, station_name, deleted_at FROM contacts")
+        .execute(&mut conn)
+        .unwrap();
+    sql_query("SELECT contact_id, action, snapshot FROM contact_revisions")
+        .execute(&mut conn)
+        .unwrap();
+    sql_query("SELECT callsign, fetched_at, ttl FROM callsign_locations")
+        .execute(&mut conn)
+        .unwrap();
+    sql_query("SELECT callsign, name, qth, lotw FROM callsign_profiles")
+        .execute(&mut conn)
+        .unwrap();
+    sql_query("SELECT contact_id, attempts, next_run FROM location_lookups")
+        .execute(&mut conn)
+        .unwrap();
+
+    // nothing left to apply the second time
+    migrate(&mut conn).unwrap();
+}
+
+#[test]
+fn newer_database() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    sql_query("INSERT INTO __diesel_schema_migrations (version) VALUES ('99990101000000')")
+        .execute(&mut conn)
+        .unwrap();
+
+    let error = migrate(&mut conn).unwrap_err().to_string();
+    assert!(error.contains("99990101000000"), "{}", error);
+}
+
+/// A live contact with only the columns that can't be null
+fn insert_contact(conn: &mut DbConnection, contact_id: &str, contest: Option<&str>) {
+    use crate::schema::contacts::dsl::*;
+    diesel::insert_into(contacts)
+        .values((
+            id.eq(contact_id),
+            recv_callsign.eq("W1AW"),
+            sent_callsign.eq("W9YB"),
+            recv_signal_report.eq("599"),
+            sent_signal_report.eq("599"),
+            timestamp.eq(time::macros::datetime!(2023-11-25 00:00)),
+            mode.eq("CW"),
+            freq_rx.eq(0i64),
+            freq_tx.eq(0i64),
+            cq_zone.eq(5i16),
+            contest_name.eq(contest),
+            is_mult_1.eq(false),
+            is_mult_2.eq(false),
+            is_mult_3.eq(false),
+            is_run_qso.eq(false),
+            is_claimed_qso.eq(true),
+            points.eq(1),
+            location_source.eq("NoLocation"),
+        ))
+        .execute(conn)
+        .unwrap();
+}
+
+fn ids(conn: &mut DbConnection, scope: &Scope) -> Vec<String> {
+    use crate::schema::contacts::dsl::*;
+    let expr = live(conn, scope).unwrap();
+    expr.select(id)
+        .order(id.asc())
+        .load::<Option<String>>(conn)
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[test]
+fn contests() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    assert_eq!(current_contest(&mut conn).unwrap(), None);
+
+    let fd = seen_contest(&mut conn, 1, "FD", None).unwrap();
+    let ss = seen_contest(&mut conn, 2, "ARRL-SS-CW", Some("C:\\N1MM\\W9YB.s3db")).unwrap();
+    assert_ne!(fd, ss);
+    assert_eq!(current_contest(&mut conn).unwrap(), Some(ss));
+
+    // seeing a contest again makes it current without adding it twice
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    assert_eq!(seen_contest(&mut conn, 1, "FD", None).unwrap(), fd);
+    assert_eq!(current_contest(&mut conn).unwrap(), Some(fd));
+
+    // ADIF contacts are matched by name
+    let seen = time::macros::datetime!(2023-11-25 00:00);
+    assert_eq!(
+        contest_named(&mut conn, Some("ARRL-SS-CW"), seen).unwrap(),
+        ss
+    );
+    let unnamed = contest_named(&mut conn, None, seen).unwrap();
+    let wpx = contest_named(&mut conn, Some("CQ-WPX-CW"), seen).unwrap();
+    assert!(![fd, ss, wpx].contains(&unnamed));
+    assert!(![fd, ss].contains(&wpx));
+    assert_eq!(contest_named(&mut conn, Some(""), seen).unwrap(), unnamed);
+    assert_eq!(current_contest(&mut conn).unwrap(), Some(fd));
+}
+
+#[test]
+fn adif_contests() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    let fd = seen_contest(&mut conn, 1, "FD", None).unwrap();
+    insert_contact(&mut conn, "a", Some("CQ-WPX-CW"));
+    insert_contact(&mut conn, "b", None);
+
+    // as imported from an ADIF file, only knowing the contest by name
+    for contact_id in ["a", "b"] {
+        let mut imported = contact(&mut conn, contact_id);
+        imported.contest_id = None;
+        write(&mut conn, &imported, &Origin::default(), None).unwrap();
+    }
+
+    assert_eq!(current_contest(&mut conn).unwrap(), Some(fd));
+    assert_eq!(ids(&mut conn, &Scope::Current), Vec::<String>::new());
+    let wpx = contact(&mut conn, "a").contest_id.unwrap();
+    let unnamed = contact(&mut conn, "b").contest_id.unwrap();
+    assert!(![fd, wpx].contains(&unnamed));
+    assert_eq!(ids(&mut conn, &Scope::Contest(wpx)), ["a"]);
+    assert_eq!(ids(&mut conn, &Scope::Contest(unnamed)), ["b"]);
+
+    // a contact N1MM logged stays in its contest when the ADIF file is read again
+    insert_contact(&mut conn, "c", Some("FD"));
+    let mut live = contact(&mut conn, "c");
+    live.contest_id = Some(fd);
+    write(&mut conn, &live, &Origin::default(), None).unwrap();
+    live.contest_id = None;
+    live.contest_name = Some("ARRL-FD".to_owned());
+    write(&mut conn, &live, &Origin::default(), None).unwrap();
+    assert_eq!(contact(&mut conn, "c").contest_id, Some(fd));
+    assert_eq!(ids(&mut conn, &Scope::Current), ["c"]);
+}
+
+#[test]
+fn adif_keeps_udp_fields() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    insert_contact(&mut conn, "a", None);
+
+    let mut udp = contact(&mut conn, "a");
+    udp.station_name = Some("FD-1".to_owned());
+    udp.country_prefix = Some("K".to_owned());
+    write(&mut conn, &udp, &Origin::default(), None).unwrap();
+
+    // read again from an ADIF file, which has neither
+    let mut imported = udp.clone();
+    imported.station_name = None;
+    imported.country_prefix = None;
+    imported.comment = Some("late".to_owned());
+    let (written, ..) = write(&mut conn, &imported, &Origin::default(), None).unwrap();
+    assert_eq!(written.station_name.as_deref(), Some("FD-1"));
+
+    let stored = contact(&mut conn, "a");
+    assert_eq!(stored.station_name.as_deref(), Some("FD-1"));
+    assert_eq!(stored.country_prefix.as_deref(), Some("K"));
+    assert_eq!(stored.comment.as_deref(), Some("late"));
+}
+
+#[test]
+fn scopes() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    let fd = seen_contest(&mut conn, 1, "FD", None).unwrap();
+    for (contact, contest, log) in [
+        ("a", Some(fd), None),
+        ("b", Some(fd), None),
+        ("c", None, Some("2022")),
+        ("d", None, None),
+    ] {
+        use crate::schema::contacts::dsl::*;
+        insert_contact(&mut conn, contact, None);
+        diesel::update(contacts.filter(id.eq(contact)))
+            .set((contest_id.eq(contest), log_name.eq(log)))
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    assert_eq!(ids(&mut conn, &Scope::Current), ["a", "b"]);
+    assert_eq!(ids(&mut conn, &Scope::Contest(fd)), ["a", "b"]);
+    assert_eq!(
+        ids(&mut conn, &Scope::Contest(fd + 1)),
+        Vec::<String>::new()
+    );
+    assert_eq!(ids(&mut conn, &Scope::Log("2022".to_owned())), ["c"]);
+}
+
+#[test]
+fn contests_from_existing_contacts() {
+    let mut conn = connection();
+    // everything before contests
+    for _ in 0..3 {
+        conn.run_next_migration(MIGRATIONS).unwrap();
+    }
+    insert_contact(&mut conn, "a", Some("FD"));
+    insert_contact(&mut conn, "b", Some("FD"));
+    insert_contact(&mut conn, "c", None);
+
+    migrate(&mut conn).unwrap();
+    let seen = time::macros::datetime!(2023-11-25 00:00);
+    let fd = contest_named(&mut conn, Some("FD"), seen).unwrap();
+    assert_eq!(ids(&mut conn, &Scope::Contest(fd)), ["a", "b"]);
+    let unnamed = contest_named(&mut conn, Some(""), seen).unwrap();
+    assert_eq!(ids(&mut conn, &Scope::Contest(unnamed)), ["c"]);
+}
+
+fn contact(conn: &mut DbConnection, contact_id: &str) -> ContactData {
+    use crate::schema::contacts::dsl::*;
+    contacts.filter(id.eq(contact_id)).first(conn).unwrap()
+}
+
+fn history(conn: &mut DbConnection) -> Vec<Revision> {
+    use crate::schema::contact_revisions::dsl::*;
+    contact_revisions.order(id.asc()).load(conn).unwrap()
+}
+
+#[test]
+fn revisions() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    let fd = seen_contest(&mut conn, 1, "FD", None).unwrap();
+    insert_contact(&mut conn, "a", Some("FD"));
+
+    let mut a = contact(&mut conn, "a");
+    a.contest_id = Some(fd);
+    write(&mut conn, &a, &Origin::default(), None).unwrap();
+    // the same contact again isn't a change
+    write(&mut conn, &a, &Origin::default(), None).unwrap();
+    a.recv_callsign = "K9YB".to_owned();
+    write(&mut conn, &a, &Origin::default(), None).unwrap();
+    assert_eq!(ids(&mut conn, &Scope::Current), ["a"]);
+
+    let origin = Origin {
+        station_name: Some("RUN1".to_owned()),
+        packet: Some("<contactdelete>...</contactdelete>".to_owned()),
+    };
+    assert_eq!(delete(&mut conn, "a", &origin).unwrap(), 1);
+    assert_eq!(delete(&mut conn, "a", &origin).unwrap(), 0);
+    assert_eq!(ids(&mut conn, &Scope::Current), Vec::<String>::new());
+    assert!(contact(&mut conn, "a").deleted_at.is_some());
+
+    let revisions = history(&mut conn);
+    let actions: Vec<_> = revisions.iter().map(|r| r.action).collect();
+    assert_eq!(actions, [Action::Replace, Action::Replace, Action::Delete]);
+    assert_eq!(revisions[2].station_name.as_deref(), Some("RUN1"));
+    assert_eq!(revisions[1].station_name, None);
+
+    // back to the first callsign, undeleted
+    let first = revisions[0].parse_snapshot().unwrap();
+    assert_eq!(first.recv_callsign, "W1AW");
+    write(&mut conn, &first, &Origin::default(), Some(Action::Restore)).unwrap();
+    assert_eq!(ids(&mut conn, &Scope::Current), ["a"]);
+    let a = contact(&mut conn, "a");
+    assert_eq!(a.recv_callsign, "W1AW");
+    assert_eq!(a.deleted_at, None);
+    assert_eq!(history(&mut conn).last().unwrap().action, Action::Restore);
+}
+
+#[test]
+fn unknown_location_source() {
+    use crate::schema::contacts::dsl::*;
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    insert_contact(&mut conn, "a", None);
+    diesel::update(contacts.filter(id.eq("a")))
+        .set(location_source.eq("Atlas"))
+        .execute(&mut conn)
+        .unwrap();
+
+    let loaded: QueryResult<ContactData> = contacts.filter(id.eq("a")).first(&mut conn);
+    let error = format!("{:?}", loaded.unwrap_err());
+    assert!(error.contains("Unknown location source `Atlas`"));
+}
+
+#[test]
+fn location_cache() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    let fetched_at = time::macros::datetime!(2023-11-25 00:00);
+    let later = time::macros::datetime!(2023-11-25 12:00);
+
+    assert_eq!(cached_location(&mut conn, "W1AW", later).unwrap(), None);
+    let missing = CallsignLocation::new("W1AW", LocationSource::HamQTH, None, fetched_at);
+    cache_location(&mut conn, &missing).unwrap();
+    assert_eq!(
+        cached_location(&mut conn, "w1aw/p", later).unwrap(),
+        Some(missing)
+    );
+
+    // found later, replacing the miss
+    let found = CallsignLocation::new(
+        "W1AW",
+        LocationSource::HamQTH,
+        Some((41.7, -72.7)),
+        fetched_at,
+    );
+    cache_location(&mut conn, &found).unwrap();
+    assert_eq!(
+        cached_location(&mut conn, "W1AW", later).unwrap(),
+        Some(found)
+    );
+
+    let expired = time::macros::datetime!(2024-01-01 00:00);
+    assert_eq!(cached_location(&mut conn, "W1AW", expired).unwrap(), None);
+}
+
+#[test]
+fn profiles() {
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+
+    let mut w1aw = Profile {
+        callsign: "W1AW".to_owned(),
+        name: Some("ARRL HQ Operators Club".to_owned()),
+        nick: None,
+        qth: Some("Newington".to_owned()),
+        country: Some("United States".to_owned()),
+        grid: Some("FN31pr".to_owned()),
+        dxcc: Some(291),
+        itu_zone: Some(8),
+        cq_zone: Some(5),
+        continent: Some("NA".to_owned()),
+        lotw: Some(true),
+        eqsl: None,
+        fetched_at: time::macros::datetime!(2023-11-25 00:00),
+    };
+    assert_eq!(profile(&mut conn, "W1AW").unwrap(), None);
+    store_profile(&mut conn, &w1aw).unwrap();
+    assert_eq!(profile(&mut conn, "w1aw/p").unwrap().as_ref(), Some(&w1aw));
+
+    w1aw.nick = Some("Hiram".to_owned());
+    store_profile(&mut conn, &w1aw).unwrap();
+    assert_eq!(profile(&mut conn, "W1AW").unwrap(), Some(w1aw));
+}
+
+#[test]
+fn lookups() {
+    use crate::schema::contacts::dsl::*;
+    let mut conn = connection();
+    migrate(&mut conn).unwrap();
+    let start = time::macros::datetime!(2023-11-25 00:00);
+
+    for contact_id in ["a", "b", "c"] {
+        insert_contact(&mut conn, contact_id, None);
+    }
+    diesel::update(contacts.filter(id.ne("c")))
+        .set(location_source.eq(LocationSource::Prefix))
+        .execute(&mut conn)
+        .unwrap();
+    delete(&mut conn, "b", &Origin::default()).unwrap();
+
+    // only live contacts located by prefix, and each once
+    assert_eq!(queue_prefix_contacts(&mut conn, start).unwrap(), 1);
+    assert_eq!(queue_prefix_contacts(&mut conn, start).unwrap(), 0);
+    assert_eq!(queue_lookup(&mut conn, "c", start).unwrap(), 1);
+    assert_eq!(queue_lookup(&mut conn, "c", start).unwrap(), 0);
+
+    let due = due_lookups(&mut conn, start, 16).unwrap();
+    let mut due_ids: Vec<_> = due.iter().map(|l| l.contact_id.as_str()).collect();
+    due_ids.sort();
+    assert_eq!(due_ids, ["a", "c"]);
+
+    let a = due.iter().find(|l| l.contact_id == "a").unwrap();
+    lookup_failed(&mut conn, a, "offline", start).unwrap();
+    let later = start + time::Duration::seconds(1);
+    let due = due_lookups(&mut conn, later, 16).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].contact_id, "c");
+
+    let retry_at = start + lookup_queue::delay(1);
+    let due = due_lookups(&mut conn, retry_at, 16).unwrap();
+    // the one due longest ago first
+    assert_eq!(due[1].contact_id, "a");
+    let failed = &due[1];
+    assert_eq!(failed.attempts, 1);
+    assert_eq!(failed.next_run, retry_at);
+    assert_eq!(failed.last_error.as_deref(), Some("offline"));
+
+    // back online
+    assert_eq!(retry_lookups(&mut conn, later).unwrap(), 1);
+    assert_eq!(due_lookups(&mut conn, later, 16).unwrap().len(), 2);
+}
+
+#[cfg(not(feature = "postgres"))]
+#[test]
+fn readers_and_writer() {
+    let path = std::env::temp_dir().join(format!("dashboard-test-{}.sql", std::process::id()));
+    let path = path.to_str().unwrap();
+    let writer = pool(path, 1, false).unwrap();
+    migrate(&mut writer.get().unwrap()).unwrap();
+    let readers = pool(path, 2, true).unwrap();
+
+    // a reader can read while a write is in progress, and sees it once committed
+    let mut reader = readers.get().unwrap();
+    let mut conn = writer.get().unwrap();
+    conn.transaction(|conn| {
+        insert_contact(conn, "a", None);
+        assert_eq!(ids_any(&mut reader), Vec::<String>::new());
+        diesel::QueryResult::Ok(())
+    })
+    .unwrap();
+    assert_eq!(ids_any(&mut reader), ["a"]);
+
+    let denied = sql_query("DELETE FROM contacts").execute(&mut reader);
+    assert!(denied.is_err());
+
+    drop((reader, conn, readers, writer));
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+    }
+}
+
+#[cfg(not(feature = "postgres"))]
+#[tokio::test]
+async fn scoped_latest() {
+    let path = std::env::temp_dir().join(format!("dashboard-latest-{}.sql", std::process::id()));
+    let path = path.to_str().unwrap();
+    let db = Database::open(path).unwrap();
+    let mut current = db.watch_latest(&Scope::Current).await;
+    let mut log = db.watch_latest(&Scope::Log("2022".to_owned())).await;
+
+    {
+        use crate::schema::contacts::dsl::*;
+        let mut conn = db.writer.get().unwrap();
+        let fd = seen_contest(&mut conn, 1, "FD", None).unwrap();
+        for (contact, contest, log) in [("a", Some(fd), None), ("b", None, Some("2022"))] {
+            insert_contact(&mut conn, contact, None);
+            diesel::update(contacts.filter(id.eq(contact)))
+                .set((contest_id.eq(contest), log_name.eq(log)))
+                .execute(&mut conn)
+                .unwrap();
+        }
+    }
+
+    // each subscriber gets the most recent contact of its own scope, once
+    db.maybe_publish(None, true, false).await.unwrap();
+    assert_eq!(current.try_recv().unwrap().unwrap().id(), Some("a"));
+    assert_eq!(log.try_recv().unwrap().unwrap().id(), Some("b"));
+    db.maybe_publish(None, true, false).await.unwrap();
+    assert!(current.try_recv().is_err());
+    assert!(log.try_recv().is_err());
+
+    drop(db);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+    }
+}
+
+/// Every contact, whatever its scope
+fn ids_any(conn: &mut DbConnection) -> Vec<String> {
+    use crate::schema::contacts::dsl::*;
+    contacts
+        .select(id)
+        .order(id.asc())
+        .load::<Option<String>>(conn)
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
@@ -1,15 +1,21 @@
 use diesel::prelude::*;
 
-use crate::{activity, contact_data};
+use crate::{activity, cabrillo, contact_data, contest, database::Scope, revision};
 
 pub async fn run_graphql_api(db: crate::database::Database) -> anyhow::Result<()> {
     let schema = async_graphql::Schema::build(
         Query {
             database: db.clone(),
         },
-        async_graphql::EmptyMutation,
-        Subscription { database: db },
+        Mutation {
+            database: db.clone(),
+        },
+        Subscription {
+            database: db.clone(),
+        },
     )
+    // for fields of the objects themselves
+    .data(db)
     .finish();
 
     let app = axum::Router::new()
@@ -39,9 +45,7 @@ async fn graphql_playground() -> impl axum::response::IntoResponse {
 }
 
 async fn graphql_handler(
-    schema: axum::extract::Extension<
-        async_graphql::Schema<Query, async_graphql::EmptyMutation, Subscription>,
-    >,
+    schema: axum::extract::Extension<async_graphql::Schema<Query, Mutation, Subscription>>,
     req: axum::extract::Json<async_graphql::Request>,
 ) -> axum::response::Json<async_graphql::Response> {
     schema.execute(req.0).await.into()
@@ -53,48 +57,122 @@ struct Query {
 
 #[async_graphql::Object]
 impl Query {
-    async fn entries(&self) -> async_graphql::Result<Vec<contact_data::ContactData>> {
-        Ok(self.database.most_recent(None, None, None).await?)
+    /// Every contest, the current one first, any of which can be passed as `contest`
+    async fn contests(&self) -> async_graphql::Result<Vec<contest::Contest>> {
+        Ok(self.database.contests().await?)
+    }
+
+    /// The contest N1MM most recently logged, unless `contest` is given
+    async fn contest(
+        &self,
+        contest: Option<i32>,
+    ) -> async_graphql::Result<Option<contest::Contest>> {
+        Ok(self.database.contest(&scope(contest, None)?).await?)
+    }
+
+    /// Names of the imported logs, any of which can be passed as `log`
+    async fn logs(&self) -> async_graphql::Result<Vec<String>> {
+        Ok(self.database.logs().await?)
+    }
+
+    /// Contacts located by prefix because their lookup failed, waiting to be looked up again
+    async fn queued_lookups(&self) -> async_graphql::Result<i64> {
+        Ok(self.database.queued_lookups().await?)
+    }
+
+    async fn entries(
+        &self,
+        contest: Option<i32>,
+        log: Option<String>,
+    ) -> async_graphql::Result<Vec<contact_data::ContactData>> {
+        Ok(self
+            .database
+            .most_recent(&scope(contest, log)?, None, None, None)
+            .await?)
     }
 
     async fn contact_count(
         &self,
+        contest: Option<i32>,
+        log: Option<String>,
         is_run: Option<bool>,
         operator: Option<String>,
     ) -> async_graphql::Result<u64> {
-        Ok(self.database.count(is_run, operator).await?)
+        Ok(self
+            .database
+            .count(&scope(contest, log)?, is_run, operator)
+            .await?)
     }
 
     async fn most_recent(
         &self,
+        contest: Option<i32>,
+        log: Option<String>,
         count: Option<u32>,
         is_run: Option<bool>,
         operator: Option<String>,
     ) -> async_graphql::Result<Vec<contact_data::ContactData>> {
-        Ok(self.database.most_recent(count, is_run, operator).await?)
+        Ok(self
+            .database
+            .most_recent(&scope(contest, log)?, count, is_run, operator)
+            .await?)
     }
 
     async fn latest(
         &self,
+        contest: Option<i32>,
+        log: Option<String>,
         is_run: Option<bool>,
         operator: Option<String>,
     ) -> async_graphql::Result<Option<contact_data::ContactData>> {
         Ok(self
             .database
-            .most_recent(Some(1), is_run, operator)
+            .most_recent(&scope(contest, log)?, Some(1), is_run, operator)
             .await?
             .pop())
     }
 
+    /// Every change to a contact, oldest first, any of which can be passed to `restore`
+    async fn history(&self, id: String) -> async_graphql::Result<Vec<revision::Revision>> {
+        Ok(self.database.history(&id).await?)
+    }
+
+    /// Deleted contacts, the most recently deleted first
+    async fn deleted(
+        &self,
+        contest: Option<i32>,
+        log: Option<String>,
+    ) -> async_graphql::Result<Vec<contact_data::ContactData>> {
+        Ok(self.database.deleted(&scope(contest, log)?).await?)
+    }
+
+    /// The whole log in Cabrillo format, with the QSO lines of `template`
+    async fn cabrillo(
+        &self,
+        contest: Option<i32>,
+        log: Option<String>,
+        template: cabrillo::Contest,
+        #[graphql(default)] header: cabrillo::Header,
+    ) -> async_graphql::Result<String> {
+        Ok(cabrillo::to_string(
+            template,
+            &header,
+            &self.database.contacts(&scope(contest, log)?).await?,
+        ))
+    }
+
     async fn active_minutes(
         &self,
+        contest: Option<i32>,
+        log: Option<String>,
         start: Option<String>,
         end: Option<String>,
         duration: Option<u32>,
     ) -> async_graphql::Result<activity::Activity> {
         use time::format_description::well_known::iso8601;
 
-        let mut act = activity::Activity::from_contacts(self.database.contacts().await?);
+        let mut act =
+            activity::Activity::from_contacts(self.database.contacts(&scope(contest, log)?).await?);
 
         let mut start = start
             .map(|t| {
@@ -139,6 +217,30 @@ impl Query {
     }
 }
 
+/// Contacts of `contest` or an imported `log`, or else of the current contest
+fn scope(contest: Option<i32>, log: Option<String>) -> async_graphql::Result<Scope> {
+    match (contest, log) {
+        (None, None) => Ok(Scope::Current),
+        (Some(contest), None) => Ok(Scope::Contest(contest)),
+        (None, Some(log)) => Ok(Scope::Log(log)),
+        (Some(_), Some(_)) => {
+            Err(anyhow::anyhow!("Only one of contest and log can be given").into())
+        }
+    }
+}
+
+struct Mutation {
+    database: crate::database::Database,
+}
+
+#[async_graphql::Object]
+impl Mutation {
+    /// Sets a contact back to a revision from `history`, undeleting it if it was deleted
+    async fn restore(&self, revision: i32) -> async_graphql::Result<contact_data::ContactData> {
+        Ok(self.database.restore(revision).await?)
+    }
+}
+
 struct Subscription {
     database: crate::database::Database,
 }
@@ -147,8 +249,15 @@ struct Subscription {
 impl Subscription {
     async fn latest(
         &self,
-    ) -> tokio_stream::wrappers::BroadcastStream<Option<contact_data::ContactData>> {
-        log::info!("Subscriber attached");
-        tokio_stream::wrappers::BroadcastStream::new(self.database.watch_latest().await)
+        contest: Option<i32>,
+        log: Option<String>,
+    ) -> async_graphql::Result<
+        tokio_stream::wrappers::BroadcastStream<Option<contact_data::ContactData>>,
+    > {
+        let scope = scope(contest, log)?;
+        log::info!("Subscriber attached to {:?}", scope);
+        Ok(tokio_stream::wrappers::BroadcastStream::new(
+            self.database.watch_latest(&scope).await,
+        ))
     }
 }
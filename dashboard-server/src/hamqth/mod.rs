@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use crate::{
+    contact_data::LocationSource,
+    database,
+    location::{Location, LocationProvider},
+    location_cache,
+    profile::Profile,
+};
+
+#[cfg(test)]
+mod test;
+
+pub const NAME: &str = "hamqth";
+pub const URL: &str = "https://www.hamqth.com";
+
+/// Requests to HamQTH at once, more wait for a slot
+const CONNECTIONS: usize = 16;
+/// How long a request can take, including reading the response
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Tries of a request before giving up on a transient failure
+const ATTEMPTS: u32 = 4;
+/// The wait before the first retry, doubled for each retry after it
+const BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// A HamQTH account, logged in to on the first lookup if it couldn't be earlier
+#[derive(Clone)]
+pub struct Session {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    url: String,
+    username: String,
+    password: String,
+    client: reqwest::Client,
+    connections: tokio::sync::Semaphore,
+    /// `None` until logged in and after the session expires
+    session_id: tokio::sync::RwLock<Option<String>>,
+}
+
+impl Session {
+    /// Doesn't log in, see [`Session::login`]
+    pub fn new(url: String, username: String, password: String) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .connect_timeout(TIMEOUT)
+            .build()?;
+        Ok(Session {
+            inner: Arc::new(Inner {
+                url: url.trim_end_matches('/').to_owned(),
+                username,
+                password,
+                client,
+                connections: tokio::sync::Semaphore::new(CONNECTIONS),
+                session_id: tokio::sync::RwLock::default(),
+            }),
+        })
+    }
+
+    /// Logs in unless already logged in
+    pub async fn login(&self) -> anyhow::Result<()> {
+        self.session_id().await.map(drop)
+    }
+
+    pub async fn query(&self, callsign: &str) -> anyhow::Result<Option<SearchData>> {
+        let _connection = self.inner.connections.acquire().await?;
+
+        let mut renewed = false;
+        loop {
+            let session_id = self.session_id().await?;
+            let response = self
+                .get(&[
+                    ("id", session_id.as_str()),
+                    ("callsign", callsign),
+                    ("prg", "rust-dashboard"),
+                ])
+                .await?;
+
+            match response.response {
+                ResponseTypes::Search(search) => return Ok(Some(*search)),
+                ResponseTypes::Session(session) => match session.error.as_deref() {
+                    Some("Callsign not found") => return Ok(None),
+                    // once, a new session failing too means something else is wrong
+                    Some("Session does not exist or expired") if !renewed => {
+                        self.expire(&session_id).await;
+                        renewed = true;
+                    }
+                    Some(e) => anyhow::bail!("HamQTH error while querying: {}", e),
+                    None => anyhow::bail!("HamQTH empty error"),
+                },
+            }
+        }
+    }
+
+    /// The current session, logging in if there isn't one
+    async fn session_id(&self) -> anyhow::Result<String> {
+        if let Some(session_id) = self.inner.session_id.read().await.as_ref() {
+            return Ok(session_id.clone());
+        }
+
+        let mut session_id = self.inner.session_id.write().await;
+        // another lookup may have logged in while this one waited
+        if let Some(session_id) = session_id.as_ref() {
+            return Ok(session_id.clone());
+        }
+
+        let response = self
+            .get(&[
+                ("u", self.inner.username.as_str()),
+                ("p", self.inner.password.as_str()),
+            ])
+            .await?;
+        let ResponseTypes::Session(session) = response.response else {
+            anyhow::bail!("received an invalid response from HamQTH while authenticating");
+        };
+        match session.session_id {
+            Some(new_id) => {
+                log::info!("Logged in to HamQTH");
+                Ok(session_id.insert(new_id).clone())
+            }
+            None => anyhow::bail!("HamQTH API error while authenticating: {:?}", session.error),
+        }
+    }
+
+    /// Forgets `expired` so the next lookup logs in again, unless that already happened
+    async fn expire(&self, expired: &str) {
+        let mut session_id = self.inner.session_id.write().await;
+        if session_id.as_deref() == Some(expired) {
+            *session_id = None;
+        }
+    }
+
+    /// Asks `xml.php`, which logs in and searches, retrying failures that may pass
+    async fn get(&self, query: &[(&str, &str)]) -> anyhow::Result<HamQTH> {
+        let mut backoff = BACKOFF;
+        let mut attempt = 1;
+        loop {
+            match self.send(query).await {
+                Ok(body) => return Ok(quick_xml::de::from_str(&body)?),
+                Err(e) if attempt < ATTEMPTS && transient(&e) => {
+                    log::debug!("HamQTH request failed, retrying in {:?}: {}", backoff, e);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn send(&self, query: &[(&str, &str)]) -> reqwest::Result<String> {
+        self.inner
+            .client
+            .get(format!("{}/xml.php", self.inner.url))
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+}
+
+/// If trying again later could work
+fn transient(e: &reqwest::Error) -> bool {
+    e.is_timeout()
+        || e.is_connect()
+        || e.is_request()
+        || e.status().is_some_and(|status| {
+            status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        })
+}
+
+#[async_trait::async_trait]
+impl LocationProvider for Session {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    async fn locate(&self, callsign: &str) -> anyhow::Result<Option<Location>> {
+        let Some(search) = self.query(callsign).await? else {
+            return Ok(None);
+        };
+        Ok(Some(Location {
+            source: LocationSource::HamQTH,
+            coordinates: search.coordinates(),
+            profile: Some(search.profile(database::now())),
+        }))
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HamQTH {
+    #[serde(rename = "$value")]
+    response: ResponseTypes,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ResponseTypes {
+    Session(SessionResponse),
+    Search(Box<SearchData>),
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SessionResponse {
+    session_id: Option<String>,
+    error: Option<String>,
+}
+
+/// Every field is optional, numbers can be empty
+#[derive(Debug, serde::Deserialize)]
+pub struct SearchData {
+    callsign: String,
+    latitude: Option<String>,
+    longitude: Option<String>,
+
+    nick: Option<String>,
+    adr_name: Option<String>,
+    qth: Option<String>,
+    country: Option<String>,
+    grid: Option<String>,
+    adif: Option<String>,
+    itu: Option<String>,
+    cq: Option<String>,
+    continent: Option<String>,
+    lotw: Option<String>,
+    eqsl: Option<String>,
+}
+
+impl SearchData {
+    pub fn coordinates(&self) -> Option<(f32, f32)> {
+        let number = |field: &Option<String>| field.as_deref().and_then(|f| f.trim().parse().ok());
+        number(&self.latitude).zip(number(&self.longitude))
+    }
+
+    pub fn profile(&self, fetched_at: time::PrimitiveDateTime) -> Profile {
+        let text = |field: &Option<String>| field.clone().filter(|f| !f.trim().is_empty());
+        let number = |field: &Option<String>| field.as_deref().and_then(|f| f.trim().parse().ok());
+        // `?` when HamQTH doesn't know
+        let flag = |field: &Option<String>| match field.as_deref() {
+            Some("Y") => Some(true),
+            Some("N") => Some(false),
+            _ => None,
+        };
+
+        Profile {
+            callsign: location_cache::normalize(&self.callsign),
+            name: text(&self.adr_name),
+            nick: text(&self.nick),
+            qth: text(&self.qth),
+            country: text(&self.country),
+            grid: text(&self.grid),
+            dxcc: number(&self.adif),
+            itu_zone: number(&self.itu),
+            cq_zone: number(&self.cq),
+            continent: text(&self.continent),
+            lotw: flag(&self.lotw),
+            eqsl: flag(&self.eqsl),
+            fetched_at,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+};
+
+use super::{Session, CONNECTIONS};
+use crate::{helpers::serve, location::LocationProvider};
+
+/// A stand-in for `xml.php`
+#[derive(Default)]
+struct HamQTH {
+    /// Answers 503 while set
+    down: AtomicBool,
+    /// Answers 503 to this many requests first
+    failures: AtomicUsize,
+    requests: AtomicUsize,
+    logins: AtomicUsize,
+    /// The session lookups must use, `None` once it has expired
+    session_id: Mutex<Option<String>>,
+    in_flight: AtomicUsize,
+    most_in_flight: AtomicUsize,
+}
+
+async fn xml_php(
+    State(state): State<Arc<HamQTH>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> (StatusCode, String) {
+    state.requests.fetch_add(1, Ordering::SeqCst);
+    if state.down.load(Ordering::SeqCst)
+        || state
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| f.checked_sub(1))
+            .is_ok()
+    {
+        return (StatusCode::SERVICE_UNAVAILABLE, String::new());
+    }
+
+    let in_flight = state.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+    state.most_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    state.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+    let body = if let Some(username) = params.get("u") {
+        if username != "W9YB" || params["p"] != "hunter2" {
+            "<session><error>Wrong user name or password</error></session>".to_owned()
+        } else {
+            let id = format!("session{}", state.logins.fetch_add(1, Ordering::SeqCst) + 1);
+            *state.session_id.lock().unwrap() = Some(id.clone());
+            format!("<session><session_id>{}</session_id></session>", id)
+        }
+    } else if params.get("id") != state.session_id.lock().unwrap().as_ref() {
+        "<session><error>Session does not exist or expired</error></session>".to_owned()
+    } else {
+        match params["callsign"].as_str() {
+            "OK2CQR" => "<search><callsign>ok2cqr</callsign><nick>Petr</nick>\
+                <qth>Neratovice</qth><country>Czech Republic</country><adif>503</adif>\
+                <itu>28</itu><cq>15</cq><grid>jo70gg</grid><adr_name>Petr Hlozek</adr_name>\
+                <lotw>Y</lotw><eqsl>?</eqsl><latitude>50.25</latitude>\
+                <longitude>14.5</longitude><continent>EU</continent></search>"
+                .to_owned(),
+            "N0GPS" => "<search><callsign>n0gps</callsign><nick>Jo</nick><qth>Denver</qth>\
+                <country>United States</country><adif>291</adif><latitude></latitude>\
+                <longitude></longitude></search>"
+                .to_owned(),
+            _ => "<session><error>Callsign not found</error></session>".to_owned(),
+        }
+    };
+    (
+        StatusCode::OK,
+        format!(
+            concat!(
+                r#"<?xml version="1.0"?>"#,
+                r#"<HamQTH version="2.8" xmlns="https://www.hamqth.com">{}</HamQTH>"#
+            ),
+            body
+        ),
+    )
+}
+
+async fn stand_in() -> (Arc<HamQTH>, String) {
+    let state = Arc::new(HamQTH::default());
+    let url = serve(
+        axum::Router::new()
+            .route("/xml.php", axum::routing::get(xml_php))
+            .with_state(state.clone()),
+    )
+    .await;
+    (state, url)
+}
+
+fn session(url: &str) -> Session {
+    Session::new(url.to_owned(), "W9YB".to_owned(), "hunter2".to_owned()).unwrap()
+}
+
+#[tokio::test]
+async fn search() {
+    let (state, url) = stand_in().await;
+    let session = session(&url);
+
+    let search = session.query("OK2CQR").await.unwrap().unwrap();
+    assert_eq!(search.coordinates(), Some((50.25, 14.5)));
+    let profile = search.profile(time::macros::datetime!(2023-11-25 00:00));
+    assert_eq!(profile.callsign, "OK2CQR");
+    assert_eq!(profile.name.as_deref(), Some("Petr Hlozek"));
+    assert_eq!(profile.qth.as_deref(), Some("Neratovice"));
+    assert_eq!(
+        (profile.dxcc, profile.itu_zone, profile.cq_zone),
+        (Some(503), Some(28), Some(15))
+    );
+    assert_eq!((profile.lotw, profile.eqsl), (Some(true), None));
+
+    assert!(session.query("XX9XXX").await.unwrap().is_none());
+    assert_eq!(state.logins.load(Ordering::SeqCst), 1);
+
+    // logs in again once the session expires
+    *state.session_id.lock().unwrap() = None;
+    assert!(session.query("OK2CQR").await.unwrap().is_some());
+    assert_eq!(state.logins.load(Ordering::SeqCst), 2);
+
+    let wrong = Session::new(url, "W9YB".to_owned(), "wrong".to_owned()).unwrap();
+    assert!(wrong.login().await.is_err());
+}
+
+#[tokio::test]
+async fn no_coordinates() {
+    let (_, url) = stand_in().await;
+    let session = session(&url);
+
+    let location = session.locate("N0GPS").await.unwrap().unwrap();
+    assert_eq!(location.coordinates, None);
+    let profile = location.profile.unwrap();
+    assert_eq!(profile.callsign, "N0GPS");
+    assert_eq!(profile.qth.as_deref(), Some("Denver"));
+    assert_eq!(profile.dxcc, Some(291));
+
+    assert_eq!(session.locate("XX9XXX").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn retries() {
+    let (state, url) = stand_in().await;
+    let session = session(&url);
+    session.login().await.unwrap();
+    state.requests.store(0, Ordering::SeqCst);
+
+    state.failures.store(2, Ordering::SeqCst);
+    assert!(session.query("OK2CQR").await.unwrap().is_some());
+    assert_eq!(state.requests.load(Ordering::SeqCst), 3);
+
+    // gives up eventually
+    state.down.store(true, Ordering::SeqCst);
+    assert!(session.query("OK2CQR").await.is_err());
+    assert_eq!(
+        state.requests.load(Ordering::SeqCst),
+        3 + super::ATTEMPTS as usize
+    );
+}
+
+#[tokio::test]
+async fn late_login() {
+    let (state, url) = stand_in().await;
+    state.down.store(true, Ordering::SeqCst);
+    let session = session(&url);
+    assert!(session.login().await.is_err());
+
+    state.down.store(false, Ordering::SeqCst);
+    assert!(session.query("OK2CQR").await.unwrap().is_some());
+    assert_eq!(state.logins.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn connections() {
+    let (state, url) = stand_in().await;
+    let session = session(&url);
+
+    let mut lookups = tokio::task::JoinSet::new();
+    for _ in 0..CONNECTIONS * 3 {
+        let session = session.clone();
+        lookups.spawn(async move { session.query("OK2CQR").await });
+    }
+    while let Some(result) = lookups.join_next().await {
+        assert!(result.unwrap().unwrap().is_some());
+    }
+
+    let most = state.most_in_flight.load(Ordering::SeqCst);
+    assert!(most > 1 && most <= CONNECTIONS, "{} at once", most);
+    assert_eq!(state.logins.load(Ordering::SeqCst), 1);
+}
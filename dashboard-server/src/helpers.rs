@@ -11,3 +11,13 @@ where
         Some(s) => T::deserialize(serde::de::value::BorrowedStrDeserializer::new(s)).map(Some),
     }
 }
+
+/// Serves `router` on a free local port, returning its URL
+#[cfg(test)]
+pub async fn serve(router: axum::Router) -> String {
+    let server =
+        axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(router.into_make_service());
+    let url = format!("http://{}/", server.local_addr());
+    tokio::spawn(server);
+    url
+}
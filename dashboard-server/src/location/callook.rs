@@ -0,0 +1,65 @@
+use super::{Location, LocationProvider};
+use crate::contact_data::LocationSource;
+
+pub const NAME: &str = "callook";
+pub const URL: &str = "https://callook.info";
+
+/// callook.info, free but only knows US callsigns
+pub struct Callook {
+    url: String,
+}
+
+impl Callook {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait::async_trait]
+impl LocationProvider for Callook {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    async fn locate(&self, callsign: &str) -> anyhow::Result<Option<Location>> {
+        // portable and foreign calls aren't in the FCC database
+        if callsign.contains('/') {
+            return Ok(None);
+        }
+
+        let body = reqwest::Client::new()
+            .get(format!(
+                "{}/{}/json",
+                self.url.trim_end_matches('/'),
+                callsign
+            ))
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        let response: CallookResponse = serde_json::from_str(&body)?;
+        match (response.status.as_str(), response.location) {
+            ("VALID", Some(location)) => Ok(Some(Location {
+                source: LocationSource::Callook,
+                coordinates: Some((location.latitude.parse()?, location.longitude.parse()?)),
+                profile: None,
+            })),
+            ("VALID" | "INVALID", _) => Ok(None),
+            (status, _) => anyhow::bail!("Callook status {} for {}", status, callsign),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct CallookResponse {
+    status: String,
+    location: Option<CallookLocation>,
+}
+
+/// Coordinates come as strings
+#[derive(Debug, serde::Deserialize)]
+struct CallookLocation {
+    latitude: String,
+    longitude: String,
+}
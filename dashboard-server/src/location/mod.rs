@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use crate::{contact_data::LocationSource, hamqth, profile::Profile};
+
+pub mod callook;
+pub mod qrz;
+
+#[cfg(test)]
+mod test;
+
+/// Where a callsign is, from one of the providers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub source: LocationSource,
+    /// Latitude and longitude, `None` from providers that know the callsign but not where it is
+    pub coordinates: Option<(f32, f32)>,
+    /// The operator, from providers that say
+    pub profile: Option<Profile>,
+}
+
+/// A service that can look up where a callsign is
+#[async_trait::async_trait]
+pub trait LocationProvider: Send + Sync {
+    /// For logs and `LOCATION_PROVIDERS`
+    fn name(&self) -> &'static str;
+
+    /// `None` if the provider doesn't know the callsign
+    async fn locate(&self, callsign: &str) -> anyhow::Result<Option<Location>>;
+}
+
+/// Providers to ask in order, the first to know a callsign wins
+#[derive(Clone, Default)]
+pub struct Chain {
+    providers: Arc<Vec<Box<dyn LocationProvider>>>,
+}
+
+impl Chain {
+    pub fn new(providers: Vec<Box<dyn LocationProvider>>) -> Self {
+        Self {
+            providers: Arc::new(providers),
+        }
+    }
+
+    /// The providers named in `LOCATION_PROVIDERS`, a comma separated list that defaults to
+    /// `hamqth`, skipping any without credentials or that can't log in, except HamQTH, which logs
+    /// in on a later lookup
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let names = std::env::var("LOCATION_PROVIDERS").unwrap_or_else(|_| hamqth::NAME.to_owned());
+
+        let mut providers: Vec<Box<dyn LocationProvider>> = Vec::new();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let provider: anyhow::Result<Box<dyn LocationProvider>> = match name {
+                hamqth::NAME => {
+                    let session = credentials("HAMQTH").and_then(|(username, password)| {
+                        hamqth::Session::new(
+                            std::env::var("HAMQTH_URL").unwrap_or_else(|_| hamqth::URL.to_owned()),
+                            username,
+                            password,
+                        )
+                    });
+                    if let Ok(session) = &session {
+                        if let Err(e) = session.login().await {
+                            println!("Could not log in to HamQTH yet: {}", e);
+                        }
+                    }
+                    session.map(|s| Box::new(s) as _)
+                }
+                qrz::NAME => match credentials("QRZ") {
+                    Ok((username, password)) => qrz::Session::new(
+                        std::env::var("QRZ_URL").unwrap_or_else(|_| qrz::URL.to_owned()),
+                        username,
+                        password,
+                    )
+                    .await
+                    .map(|s| Box::new(s) as _),
+                    Err(e) => Err(e),
+                },
+                callook::NAME => Ok(Box::new(callook::Callook::new(
+                    std::env::var("CALLOOK_URL").unwrap_or_else(|_| callook::URL.to_owned()),
+                ))),
+                name => anyhow::bail!("Unknown location provider `{}`", name),
+            };
+
+            match provider {
+                Ok(provider) => providers.push(provider),
+                Err(e) => println!("Skipping location provider {}: {}", name, e),
+            }
+        }
+
+        Ok(Self::new(providers))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+
+    /// The location from the first provider with coordinates for `callsign`, keeping the profile
+    /// of one that knew the callsign but not where it is, an error only if none had coordinates
+    /// and one of them failed, so a miss isn't remembered when a provider was down
+    pub async fn locate(&self, callsign: &str) -> anyhow::Result<Option<Location>> {
+        let mut error = None;
+        let mut without_coordinates: Option<Location> = None;
+        for provider in self.providers.iter() {
+            match provider.locate(callsign).await {
+                Ok(Some(mut location)) if location.coordinates.is_some() => {
+                    if location.profile.is_none() {
+                        location.profile = without_coordinates.and_then(|l| l.profile);
+                    }
+                    return Ok(Some(location));
+                }
+                Ok(Some(location)) => {
+                    log::debug!("{} has no coordinates for {}", provider.name(), callsign);
+                    without_coordinates = without_coordinates
+                        .filter(|l| l.profile.is_some())
+                        .or(Some(location));
+                }
+                Ok(None) => log::debug!("{} has no location for {}", provider.name(), callsign),
+                Err(e) => {
+                    log::warn!("{} error looking up {}: {}", provider.name(), callsign, e);
+                    error = Some(e);
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(without_coordinates),
+        }
+    }
+}
+
+/// `{provider}_USERNAME` and `{provider}_PASSWORD`
+fn credentials(provider: &str) -> anyhow::Result<(String, String)> {
+    let var = |name: &str| {
+        let name = format!("{}_{}", provider, name);
+        std::env::var(&name).map_err(|_| anyhow::anyhow!("{} is not set", name))
+    };
+    Ok((var("USERNAME")?, var("PASSWORD")?))
+}
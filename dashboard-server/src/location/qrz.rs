@@ -0,0 +1,129 @@
+use super::{Location, LocationProvider};
+use crate::contact_data::LocationSource;
+
+pub const NAME: &str = "qrz";
+/// The XML data service, needs a subscription for coordinates
+pub const URL: &str = "https://xmldata.qrz.com/xml/current/";
+
+/// A logged in session with the QRZ.com XML API
+pub struct Session {
+    url: String,
+    username: String,
+    password: String,
+    key: tokio::sync::RwLock<String>,
+}
+
+impl Session {
+    pub async fn new(url: String, username: String, password: String) -> anyhow::Result<Self> {
+        let key = login(&url, &username, &password).await?;
+        Ok(Self {
+            url,
+            username,
+            password,
+            key: tokio::sync::RwLock::new(key),
+        })
+    }
+
+    async fn query(&self, callsign: &str, key: &str) -> anyhow::Result<QrzDatabase> {
+        let body = reqwest::Client::new()
+            .get(&self.url)
+            .query(&[("s", key), ("callsign", callsign)])
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(quick_xml::de::from_str(&body)?)
+    }
+}
+
+#[async_trait::async_trait]
+impl LocationProvider for Session {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    async fn locate(&self, callsign: &str) -> anyhow::Result<Option<Location>> {
+        let key = self.key.read().await.clone();
+        let mut response = self.query(callsign, &key).await?;
+
+        if response.session.error.as_deref().is_some_and(expired) {
+            let mut current = self.key.write().await;
+            // another lookup may have logged in again already
+            if *current == key {
+                *current = login(&self.url, &self.username, &self.password).await?;
+            }
+            let key = current.clone();
+            drop(current);
+            response = self.query(callsign, &key).await?;
+        }
+
+        match (response.callsign, response.session.error) {
+            (
+                Some(QrzCallsign {
+                    lat: Some(latitude),
+                    lon: Some(longitude),
+                    ..
+                }),
+                _,
+            ) => Ok(Some(Location {
+                source: LocationSource::QRZ,
+                coordinates: Some((latitude, longitude)),
+                profile: None,
+            })),
+            (Some(_), _) => Ok(None),
+            (None, Some(e)) if e.starts_with("Not found") => Ok(None),
+            (None, e) => anyhow::bail!("QRZ error while querying: {:?}", e),
+        }
+    }
+}
+
+/// If the session key needs logging in again
+fn expired(error: &str) -> bool {
+    error.starts_with("Session Timeout") || error.starts_with("Invalid session key")
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QrzDatabase {
+    #[serde(rename = "Callsign")]
+    callsign: Option<QrzCallsign>,
+    #[serde(rename = "Session")]
+    session: QrzSession,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QrzSession {
+    #[serde(rename = "Key")]
+    key: Option<String>,
+    #[serde(rename = "Error")]
+    error: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct QrzCallsign {
+    call: String,
+    lat: Option<f32>,
+    lon: Option<f32>,
+}
+
+async fn login(url: &str, username: &str, password: &str) -> anyhow::Result<String> {
+    let body = reqwest::Client::new()
+        .get(url)
+        .query(&[
+            ("username", username),
+            ("password", password),
+            ("agent", "rust-dashboard"),
+        ])
+        .send()
+        .await?
+        .text()
+        .await?;
+
+    let response: QrzDatabase = quick_xml::de::from_str(&body)?;
+    match response.session.key {
+        Some(key) => Ok(key),
+        None => anyhow::bail!(
+            "QRZ API error while authenticating: {:?}",
+            response.session.error
+        ),
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use axum::extract::{Path, Query, State};
+
+use super::{callook::Callook, qrz, Chain, Location, LocationProvider};
+use crate::{contact_data::LocationSource, helpers::serve, profile::Profile};
+
+enum Answer {
+    Found,
+    /// Knows the operator but not where they are
+    Known,
+    Missing,
+    Down,
+}
+
+/// A provider that always gives the same answer and counts how often it was asked
+struct Stub(Answer, Arc<AtomicUsize>);
+
+#[async_trait::async_trait]
+impl LocationProvider for Stub {
+    fn name(&self) -> &'static str {
+        "stub"
+    }
+
+    async fn locate(&self, _callsign: &str) -> anyhow::Result<Option<Location>> {
+        self.1.fetch_add(1, Ordering::Relaxed);
+        match self.0 {
+            Answer::Found => Ok(Some(Location {
+                source: LocationSource::HamQTH,
+                coordinates: Some((1.0, 2.0)),
+                profile: None,
+            })),
+            Answer::Known => Ok(Some(Location {
+                source: LocationSource::HamQTH,
+                coordinates: None,
+                profile: Some(profile()),
+            })),
+            Answer::Missing => Ok(None),
+            Answer::Down => anyhow::bail!("down"),
+        }
+    }
+}
+
+fn profile() -> Profile {
+    Profile {
+        callsign: "W1AW".to_owned(),
+        name: Some("ARRL".to_owned()),
+        nick: None,
+        qth: Some("Newington".to_owned()),
+        country: None,
+        grid: None,
+        dxcc: None,
+        itu_zone: None,
+        cq_zone: None,
+        continent: None,
+        lotw: None,
+        eqsl: None,
+        fetched_at: time::macros::datetime!(2023-11-25 00:00),
+    }
+}
+
+/// A chain of stubs and how often each is asked
+fn chain(answers: Vec<Answer>) -> (Chain, impl Fn() -> Vec<usize>) {
+    let counts: Vec<_> = answers.iter().map(|_| Arc::default()).collect();
+    let providers = answers
+        .into_iter()
+        .zip(&counts)
+        .map(|(a, c)| Box::new(Stub(a, Arc::clone(c))) as Box<dyn LocationProvider>)
+        .collect();
+    let asked = move || counts.iter().map(|c| c.load(Ordering::Relaxed)).collect();
+    (Chain::new(providers), asked)
+}
+
+#[tokio::test]
+async fn chain_order() {
+    let (providers, asked) = chain(vec![Answer::Missing, Answer::Found, Answer::Found]);
+    let location = providers.locate("W1AW").await.unwrap().unwrap();
+    assert_eq!(location.coordinates, Some((1.0, 2.0)));
+    assert_eq!(asked(), [1, 1, 0]);
+
+    // a provider being down doesn't stop the next one answering
+    let (providers, asked) = chain(vec![Answer::Down, Answer::Found]);
+    assert!(providers.locate("W1AW").await.unwrap().is_some());
+    assert_eq!(asked(), [1, 1]);
+
+    let (providers, _) = chain(vec![Answer::Missing, Answer::Missing]);
+    assert_eq!(providers.locate("W1AW").await.unwrap(), None);
+
+    // the operator from a provider without coordinates comes with the next one's location
+    let (providers, asked) = chain(vec![Answer::Known, Answer::Found]);
+    let location = providers.locate("W1AW").await.unwrap().unwrap();
+    assert_eq!(location.coordinates, Some((1.0, 2.0)));
+    assert_eq!(location.profile, Some(profile()));
+    assert_eq!(asked(), [1, 1]);
+
+    // and is kept when nothing knows where the callsign is
+    let (providers, _) = chain(vec![Answer::Known, Answer::Missing]);
+    let location = providers.locate("W1AW").await.unwrap().unwrap();
+    assert_eq!(location.coordinates, None);
+    assert_eq!(location.profile, Some(profile()));
+
+    // but a miss with one down isn't certain
+    let (providers, _) = chain(vec![Answer::Missing, Answer::Down]);
+    assert!(providers.locate("W1AW").await.is_err());
+
+    let (providers, _) = chain(vec![]);
+    assert!(providers.is_empty());
+    assert_eq!(providers.locate("W1AW").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn from_env() {
+    // QRZ.com has no credentials and is skipped, Callook doesn't need any
+    std::env::set_var("LOCATION_PROVIDERS", "qrz, callook");
+    std::env::remove_var("QRZ_USERNAME");
+    std::env::set_var("QRZ_PASSWORD", "hunter2");
+
+    let providers = Chain::from_env().await.unwrap();
+    let names: Vec<_> = providers.providers.iter().map(|p| p.name()).collect();
+    assert_eq!(names, ["callook"]);
+
+    std::env::set_var("LOCATION_PROVIDERS", "qrz, nope");
+    assert!(Chain::from_env().await.is_err());
+}
+
+#[tokio::test]
+async fn callook() {
+    async fn lookup(Path(callsign): Path<String>) -> &'static str {
+        match callsign.as_str() {
+            "W1AW" => concat!(
+                r#"{"status":"VALID","type":"CLUB","current":{"callsign":"W1AW"},"location":"#,
+                r#"{"latitude":"41.714775","longitude":"-72.727260","gridsquare":"FN31pr"}}"#
+            ),
+            "W9UP" => r#"{"status":"UPDATING"}"#,
+            _ => r#"{"status":"INVALID"}"#,
+        }
+    }
+    let url = serve(axum::Router::new().route("/:callsign/json", axum::routing::get(lookup))).await;
+    let callook = Callook::new(url);
+
+    assert_eq!(
+        callook.locate("W1AW").await.unwrap(),
+        Some(Location {
+            source: LocationSource::Callook,
+            coordinates: Some((41.714775, -72.72726)),
+            profile: None,
+        })
+    );
+    assert_eq!(callook.locate("DL1ABC").await.unwrap(), None);
+    assert_eq!(callook.locate("VE3/W1AW").await.unwrap(), None);
+    assert!(callook.locate("W9UP").await.is_err());
+}
+
+/// The key lookups must use, `None` once it has expired
+#[derive(Default)]
+struct QrzState {
+    logins: AtomicUsize,
+    key: Mutex<Option<String>>,
+}
+
+async fn qrz_api(
+    State(state): State<Arc<QrzState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> String {
+    let body = if let Some(username) = params.get("username") {
+        if username != "W9YB" || params.get("password").map(String::as_str) != Some("hunter2") {
+            "<Session><Error>Username/password incorrect</Error></Session>".to_owned()
+        } else {
+            let key = format!("key{}", state.logins.fetch_add(1, Ordering::Relaxed) + 1);
+            *state.key.lock().unwrap() = Some(key.clone());
+            format!("<Session><Key>{}</Key><Count>0</Count></Session>", key)
+        }
+    } else if params.get("s") != state.key.lock().unwrap().as_ref() {
+        "<Session><Error>Session Timeout</Error></Session>".to_owned()
+    } else {
+        match params["callsign"].as_str() {
+            "W1AW" => "<Callsign><call>W1AW</call><lat>41.714775</lat><lon>-72.727260</lon>\
+                <grid>FN31pr</grid></Callsign><Session><Key>key</Key></Session>"
+                .to_owned(),
+            "N0GPS" => "<Callsign><call>N0GPS</call></Callsign><Session></Session>".to_owned(),
+            callsign => format!("<Session><Error>Not found: {}</Error></Session>", callsign),
+        }
+    };
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8" ?>
+        <QRZDatabase version="1.34" xmlns="http://xmldata.qrz.com">{}</QRZDatabase>"#,
+        body
+    )
+}
+
+#[tokio::test]
+async fn qrz() {
+    let state = Arc::new(QrzState::default());
+    let url = serve(
+        axum::Router::new()
+            .route("/", axum::routing::get(qrz_api))
+            .with_state(state.clone()),
+    )
+    .await;
+
+    assert!(
+        qrz::Session::new(url.clone(), "W9YB".to_owned(), "wrong".to_owned())
+            .await
+            .is_err()
+    );
+    let session = qrz::Session::new(url, "W9YB".to_owned(), "hunter2".to_owned())
+        .await
+        .unwrap();
+
+    let w1aw = Some(Location {
+        source: LocationSource::QRZ,
+        coordinates: Some((41.714775, -72.72726)),
+        profile: None,
+    });
+    assert_eq!(session.locate("W1AW").await.unwrap(), w1aw);
+    assert_eq!(session.locate("N0GPS").await.unwrap(), None);
+    assert_eq!(session.locate("XX9XXX").await.unwrap(), None);
+    assert_eq!(state.logins.load(Ordering::Relaxed), 1);
+
+    // logs in again once the key expires
+    *state.key.lock().unwrap() = None;
+    assert_eq!(session.locate("W1AW").await.unwrap(), w1aw);
+    assert_eq!(state.logins.load(Ordering::Relaxed), 2);
+}
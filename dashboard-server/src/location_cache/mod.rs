@@ -0,0 +1,72 @@
+use crate::contact_data::LocationSource;
+
+#[cfg(test)]
+mod test;
+
+/// How long a found location is used before looking it up again
+pub const FOUND_TTL: time::Duration = time::Duration::days(30);
+/// How long a callsign that wasn't found is left before trying again
+pub const NOT_FOUND_TTL: time::Duration = time::Duration::days(1);
+
+/// A looked up location, shared by every contact with the callsign
+#[derive(Debug, Clone, PartialEq, diesel::Queryable, diesel::Selectable, diesel::Insertable)]
+#[diesel(table_name = crate::schema::callsign_locations)]
+#[diesel(check_for_backend(crate::database::DbBackend))]
+pub struct CallsignLocation {
+    /// See [`normalize`]
+    pub callsign: String,
+
+    /// `NoLocation` when the lookup found nothing
+    pub location_source: LocationSource,
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+
+    pub fetched_at: time::PrimitiveDateTime,
+    /// Seconds until it should be looked up again
+    pub ttl: i32,
+}
+
+impl CallsignLocation {
+    /// A lookup of `callsign` just now, `coordinates` is `None` if nothing was found
+    pub fn new(
+        callsign: &str,
+        source: LocationSource,
+        coordinates: Option<(f32, f32)>,
+        fetched_at: time::PrimitiveDateTime,
+    ) -> Self {
+        let (location_source, ttl) = match coordinates {
+            Some(_) => (source, FOUND_TTL),
+            None => (LocationSource::NoLocation, NOT_FOUND_TTL),
+        };
+        Self {
+            callsign: normalize(callsign),
+            location_source,
+            latitude: coordinates.map(|c| c.0),
+            longitude: coordinates.map(|c| c.1),
+            fetched_at,
+            ttl: ttl.whole_seconds() as i32,
+        }
+    }
+
+    pub fn coordinates(&self) -> Option<(f32, f32)> {
+        self.latitude.zip(self.longitude)
+    }
+
+    /// If it can be used at `now` rather than looking the callsign up again
+    pub fn is_fresh(&self, now: time::PrimitiveDateTime) -> bool {
+        now < self.fetched_at + time::Duration::seconds(self.ttl.into())
+    }
+}
+
+/// Upper case without the designators that don't move the station, so `w1aw/p` shares the
+/// location of `W1AW` but `VE3/W1AW` doesn't
+pub fn normalize(callsign: &str) -> String {
+    let mut callsign = callsign.trim().to_ascii_uppercase();
+    while let Some(stripped) = ["/P", "/QRP"]
+        .iter()
+        .find_map(|suffix| callsign.strip_suffix(suffix))
+    {
+        callsign = stripped.to_owned();
+    }
+    callsign
+}
@@ -0,0 +1,39 @@
+use time::macros::datetime;
+
+use super::{normalize, CallsignLocation};
+use crate::contact_data::LocationSource;
+
+#[test]
+fn normalized() {
+    assert_eq!(normalize(" w1aw "), "W1AW");
+    assert_eq!(normalize("W1AW/P"), "W1AW");
+    assert_eq!(normalize("w1aw/qrp"), "W1AW");
+    assert_eq!(normalize("W1AW/P/QRP"), "W1AW");
+    assert_eq!(normalize("VE3/W1AW"), "VE3/W1AW");
+    assert_eq!(normalize("W1AW/M"), "W1AW/M");
+}
+
+#[test]
+fn freshness() {
+    let fetched_at = datetime!(2023-11-25 00:00);
+    let found = CallsignLocation::new(
+        "w1aw/p",
+        LocationSource::HamQTH,
+        Some((41.7, -72.7)),
+        fetched_at,
+    );
+    assert_eq!(found.callsign, "W1AW");
+    assert_eq!(found.coordinates(), Some((41.7, -72.7)));
+    assert!(found.is_fresh(datetime!(2023-12-20 00:00)));
+    assert!(!found.is_fresh(datetime!(2023-12-25 00:00)));
+
+    // not found is tried again sooner
+    let missing = CallsignLocation::new("W1AW", LocationSource::HamQTH, None, fetched_at);
+    assert!(matches!(
+        missing.location_source,
+        LocationSource::NoLocation
+    ));
+    assert_eq!(missing.coordinates(), None);
+    assert!(missing.is_fresh(datetime!(2023-11-25 12:00)));
+    assert!(!missing.is_fresh(datetime!(2023-11-26 00:00)));
+}
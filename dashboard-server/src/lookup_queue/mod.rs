@@ -0,0 +1,78 @@
+use crate::{database::Database, location};
+
+#[cfg(test)]
+mod test;
+
+/// How often the queue is checked for lookups that are due
+const POLL: std::time::Duration = std::time::Duration::from_secs(5);
+/// Lookups tried each time the queue is checked
+const BATCH: i64 = 16;
+/// The wait after the first failure, doubled for each failure after it
+const FIRST_DELAY: time::Duration = time::Duration::seconds(30);
+const MAX_DELAY: time::Duration = time::Duration::minutes(15);
+
+/// A contact waiting for its location to be looked up again
+#[derive(Debug, Clone, PartialEq, diesel::Queryable, diesel::Selectable, diesel::Insertable)]
+#[diesel(table_name = crate::schema::location_lookups)]
+#[diesel(check_for_backend(crate::database::DbBackend))]
+pub struct Lookup {
+    pub contact_id: String,
+    /// Failures so far
+    pub attempts: i32,
+    pub queued_at: time::PrimitiveDateTime,
+    pub next_run: time::PrimitiveDateTime,
+    pub last_error: Option<String>,
+}
+
+impl Lookup {
+    pub fn new(contact_id: &str, now: time::PrimitiveDateTime) -> Self {
+        Self {
+            contact_id: contact_id.to_owned(),
+            attempts: 0,
+            queued_at: now,
+            next_run: now,
+            last_error: None,
+        }
+    }
+}
+
+/// How long to wait after `attempts` failed lookups
+pub fn delay(attempts: i32) -> time::Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (FIRST_DELAY * 2i32.pow(doublings)).min(MAX_DELAY)
+}
+
+/// Works through the queue until the server stops, starting with contacts already located by
+/// prefix
+pub async fn run(db: Database, providers: location::Chain) -> anyhow::Result<()> {
+    let queued = db.queue_prefix_contacts().await?;
+    if queued > 0 {
+        log::info!("Queued lookups of {} contacts located by prefix", queued);
+    }
+
+    loop {
+        if let Err(e) = work(&db, &providers).await {
+            log::warn!("Error working through the lookup queue: {}", e);
+        }
+        tokio::time::sleep(POLL).await;
+    }
+}
+
+async fn work(db: &Database, providers: &location::Chain) -> anyhow::Result<()> {
+    for lookup in db.due_lookups(BATCH).await? {
+        match db.enrich(&lookup.contact_id, providers).await {
+            Ok(()) => {
+                db.finish_lookup(&lookup.contact_id).await?;
+                // the providers are back, so don't leave the rest waiting
+                if lookup.attempts > 0 {
+                    db.retry_lookups().await?;
+                }
+            }
+            Err(e) => {
+                log::debug!("Lookup of {} failed again: {}", lookup.contact_id, e);
+                db.lookup_failed(&lookup, &e.to_string()).await?;
+            }
+        }
+    }
+    Ok(())
+}
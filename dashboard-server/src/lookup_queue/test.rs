@@ -0,0 +1,10 @@
+use super::{delay, FIRST_DELAY, MAX_DELAY};
+
+#[test]
+fn backoff() {
+    assert_eq!(delay(1), FIRST_DELAY);
+    assert_eq!(delay(2), FIRST_DELAY * 2);
+    assert_eq!(delay(3), FIRST_DELAY * 4);
+    assert_eq!(delay(6), MAX_DELAY);
+    assert_eq!(delay(i32::MAX), MAX_DELAY);
+}
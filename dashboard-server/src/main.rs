@@ -4,73 +4,260 @@
 
 mod activity;
 mod adif;
+mod bench;
+mod cabrillo;
 mod contact_data;
+mod contest;
 mod database;
 mod graphql;
 mod hamqth;
 mod helpers;
+mod location;
+mod location_cache;
+mod lookup_queue;
+mod n1mm;
 mod prefix;
+mod profile;
+mod revision;
 mod rst;
 mod schema;
 mod udp;
 mod xml;
 
-use diesel::prelude::*;
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     pretty_env_logger::init();
 
-    let hamqth_session = hamqth::Session::new(
-        std::env::var("HAMQTH_USERNAME")?,
-        std::env::var("HAMQTH_PASSWORD")?,
-    )
-    .await
-    .map_err(|e| {
-        println!("HamQTH error: {}", e);
-        ()
-    })
-    .ok();
-
-    if hamqth_session.is_none() {
-        println!("Could not log in to HamQTH");
+    // the same variable the diesel CLI reads
+    #[cfg(not(feature = "postgres"))]
+    let database_url = {
+        let path = std::env::var("DATABASE_URL").unwrap_or_else(|_| "db.sql".to_owned());
+        if let Some(parent) = std::path::Path::new(&path).parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        path
+    };
+    #[cfg(feature = "postgres")]
+    let database_url = std::env::var("DATABASE_URL")
+        .map_err(|_| anyhow::anyhow!("DATABASE_URL must be set to a PostgreSQL database"))?;
+    let db = database::Database::open(&database_url)?;
+
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        None => {}
+        Some("cabrillo") => {
+            // cabrillo <contest> [output] [--name value]...
+            let contest: cabrillo::Contest = args
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Missing contest"))?
+                .parse()?;
+            let mut args = args.peekable();
+            let output = args.next_if(|a| !a.starts_with("--"));
+            let header = cabrillo::Header::from_args(args)?;
+
+            let log = cabrillo::to_string(
+                contest,
+                &header,
+                &db.contacts(&database::Scope::Current).await?,
+            );
+            match output {
+                Some(path) => std::fs::write(path, log)?,
+                None => print!("{}", log),
+            }
+            return Ok(());
+        }
+        Some("adif") => {
+            // adif [output.adi|output.adx]
+            let contacts = db.contacts(&database::Scope::Current).await?;
+            let output = args.next().map(std::path::PathBuf::from);
+            let adx = output
+                .as_ref()
+                .and_then(|p| p.extension())
+                .is_some_and(|e| e.eq_ignore_ascii_case("adx"));
+            match output {
+                Some(path) if adx => adif::write_adx(std::fs::File::create(path)?, &contacts)?,
+                Some(path) => adif::write_adif(std::fs::File::create(path)?, &contacts)?,
+                None => adif::write_adif(std::io::stdout().lock(), &contacts)?,
+            }
+            return Ok(());
+        }
+        Some("import-cabrillo") => {
+            // import-cabrillo <file> [log name] [contest]
+            let path = std::path::PathBuf::from(
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing Cabrillo file"))?,
+            );
+            let name = match args.next() {
+                Some(name) => name,
+                None => path
+                    .file_stem()
+                    .ok_or_else(|| anyhow::anyhow!("Missing log name"))?
+                    .to_string_lossy()
+                    .into_owned(),
+            };
+            let contest = args.next().map(|c| c.parse()).transpose()?;
+
+            let log = cabrillo::parse(&std::fs::read_to_string(&path)?, contest)?;
+            for (line, error) in &log.skipped {
+                log::warn!("Skipped line {} of {}: {}", line, path.display(), error);
+            }
+            let contest_name = log.get("CONTEST");
+            let contacts: Vec<_> = (1..)
+                .zip(log.qsos.iter().cloned())
+                .map(|(number, qso)| {
+                    contact_data::ContactData::from_cabrillo(&name, number, contest_name, qso)
+                })
+                .collect();
+            db.import_log(&name, &contacts).await?;
+            println!(
+                "Imported {} contacts into log {}, skipped {}",
+                contacts.len(),
+                name,
+                log.skipped.len()
+            );
+            return Ok(());
+        }
+        Some("validate-adif") => {
+            // validate-adif <file.adi|file.adx>
+            let path = std::path::PathBuf::from(
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("Missing ADIF file"))?,
+            );
+            let issues = adif::validate_file(&path)?;
+            for issue in &issues {
+                println!("{}", issue);
+            }
+            println!("{} has {} issues", path.display(), issues.len());
+            return Ok(());
+        }
+        Some("bench-ingest") => {
+            // bench-ingest [contacts] [pollers]
+            let contacts = args.next().map(|c| c.parse()).transpose()?.unwrap_or(500);
+            let pollers = args.next().map(|p| p.parse()).transpose()?.unwrap_or(32);
+            bench::ingest(contacts, pollers).await?;
+            return Ok(());
+        }
+        Some("n1mm-contests") => {
+            // n1mm-contests <file.s3db>
+            let path = args
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("Missing N1MM database"))?;
+            for contest in n1mm::Database::open(path)?.contests()? {
+                println!(
+                    "{:>4} {:<24} {:<20} {:<10} {} contacts",
+                    contest.number,
+                    contest.name,
+                    contest.start.unwrap_or_default(),
+                    contest.callsign.unwrap_or_default(),
+                    contest.contacts
+                );
+            }
+            return Ok(());
+        }
+        Some(command) => return Err(anyhow::anyhow!("Unknown command `{}`", command)),
     }
 
-    let adif_records = match &std::fs::read_to_string("W9YB.adi") {
-        Ok(text) => adif::read_adif(text)?
-            .map(|r| r.map(|r| contact_data::ContactData::from(r)))
-            .collect::<Result<Vec<_>, _>>()?,
-        Err(_) => Vec::new(),
+    let providers = location::Chain::from_env().await?;
+    if providers.is_empty() {
+        println!("No location providers, locating callsigns by prefix");
+    }
+
+    let mut adif_count = 0;
+    let mut adif_tasks = tokio::task::JoinSet::new();
+
+    // backfill from N1MM's own database, N1MM_CONTEST is a `contestnr` from `n1mm-contests`
+    if let Ok(path) = std::env::var("N1MM_DATABASE") {
+        let n1mm_db = n1mm::Database::open(&path)?;
+        let contest = match std::env::var("N1MM_CONTEST") {
+            Ok(number) => n1mm_db.contest(number.parse()?)?,
+            Err(_) => n1mm_db.latest_contest()?,
+        };
+        let entries = n1mm_db.log(&contest)?;
+        let contest_id = db
+            .seen_contest(contest.number, &contest.name, Some(&path))
+            .await?;
+        println!(
+            "Importing {} contacts of {} from {}",
+            entries.len(),
+            contest.name,
+            path
+        );
+
+        for entry in entries {
+            let mut d = contact_data::ContactData::from(entry);
+            d.contest_id = Some(contest_id);
+            let (db, providers) = (db.clone(), providers.clone());
+            adif_tasks.spawn(async move {
+                db.update_and_fetch_location(&providers, &d, &revision::Origin::default(), false)
+                    .await
+            });
+        }
+    }
+
+    let mut import = |record: anyhow::Result<adif::N1MMAdifRecord>| -> anyhow::Result<()> {
+        let d = contact_data::ContactData::from(record?);
+        adif_count += 1;
+
+        let (db, providers) = (db.clone(), providers.clone());
+        adif_tasks.spawn(async move {
+            db.update_and_fetch_location(&providers, &d, &revision::Origin::default(), false)
+                .await
+        });
+        Ok(())
     };
-    println!("ADIF record count: {}", adif_records.len());
-
-    let manager = diesel::r2d2::ConnectionManager::<SqliteConnection>::new("db.sql");
-    let pool = diesel::r2d2::Pool::builder()
-        .test_on_check_out(true)
-        .max_size(1)
-        .build(manager)?;
-
-    let db = database::Database::new(pool).await?;
-
-    if let Some(ref session) = hamqth_session {
-        let mut adif_tasks = tokio::task::JoinSet::new();
-        for d in adif_records {
-            let db = db.clone();
-            let session = session.clone();
-            adif_tasks
-                .spawn(async move { db.update_and_fetch_location(&session, &d, false).await });
+
+    // warn about what doesn't conform in the file the import reads, without stopping it
+    let adif_file = ["W9YB.adi", "W9YB.adx"]
+        .map(std::path::Path::new)
+        .into_iter()
+        .find(|p| p.exists());
+    if let Some(path) = adif_file {
+        match adif::validate_file(path) {
+            Ok(issues) => {
+                for issue in issues {
+                    log::warn!("{}: {}", path.display(), issue);
+                }
+            }
+            Err(e) => log::warn!("Could not validate {}: {}", path.display(), e),
         }
-        while let Some(res) = adif_tasks.join_next().await {
-            res??;
+    }
+
+    if let Ok(file) = std::fs::File::open("W9YB.adi") {
+        let mut records = adif::read_adif_stream(std::io::BufReader::new(file))?.lenient();
+        for record in &mut records {
+            import(record.map_err(Into::into))?;
+        }
+
+        for diagnostic in records.diagnostics() {
+            log::warn!("Skipped ADIF record: {}", diagnostic);
+        }
+    } else if let Ok(text) = std::fs::read_to_string("W9YB.adx") {
+        let mut records = adif::read_adx(&text)?.lenient();
+        for record in &mut records {
+            import(record.map_err(Into::into))?;
+        }
+
+        for diagnostic in records.diagnostics() {
+            log::warn!("Skipped ADX record: {}", diagnostic);
         }
     }
+    println!("ADIF record count: {}", adif_count);
+
+    while let Some(res) = adif_tasks.join_next().await {
+        res??;
+    }
 
     let mut tasks = tokio::task::JoinSet::new();
     tasks.spawn(graphql::run_graphql_api(db.clone()));
-    tasks.spawn(udp::udp_receiver(db.clone(), hamqth_session));
+    if !providers.is_empty() {
+        tasks.spawn(lookup_queue::run(db.clone(), providers.clone()));
+    }
+    tasks.spawn(udp::udp_receiver(db.clone(), providers));
 
     tasks.spawn(async move {
-        let mut recv = db.watch_latest().await;
+        let mut recv = db.watch_latest(&database::Scope::Current).await;
         println!("here");
         loop {
             log::info!("Got value from subscription: {:?}", recv.recv().await);
@@ -0,0 +1,208 @@
+use crate::rst::RST;
+
+#[cfg(test)]
+mod test;
+
+/// A contest in an N1MM Logger+ database
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contest {
+    /// `contestnr` in N1MM's UDP packets
+    pub number: i32,
+    pub name: String,
+    pub start: Option<String>,
+    /// The callsign the contest was set up with
+    pub callsign: Option<String>,
+    pub contacts: u64,
+}
+
+/// A row of `DXLOG`
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub id: String,
+    pub contest: Contest,
+
+    pub recv_callsign: String,
+    pub recv_signal_report: RST,
+    pub sent_signal_report: RST,
+    pub timestamp: time::PrimitiveDateTime,
+
+    pub mode: String,
+    /// Lower edge of the band in MHz
+    pub band: f64,
+    pub freq_rx: i64,
+    pub freq_tx: i64,
+
+    pub exchange1: Option<String>,
+    pub section: Option<String>,
+    pub prefix_wpx: Option<String>,
+    pub cq_zone: i16,
+    pub operator: Option<String>,
+
+    pub is_mult_1: bool,
+    pub is_mult_2: bool,
+    pub is_mult_3: bool,
+
+    pub is_run_qso: bool,
+    pub is_claimed_qso: bool,
+    pub points: i32,
+
+    pub grid_square: Option<String>,
+    pub sent_number: Option<i32>,
+    pub recv_number: Option<i32>,
+    pub recv_name: Option<String>,
+    pub recv_power: Option<String>,
+    pub recv_qth: Option<String>,
+    pub prec: Option<String>,
+    pub ck: Option<i32>,
+    pub comment: Option<String>,
+    pub continent: Option<String>,
+    pub country_prefix: Option<String>,
+    pub radio_number: Option<i32>,
+    pub station_name: Option<String>,
+    pub net_bios_name: Option<String>,
+}
+
+/// An N1MM Logger+ `.s3db` file, opened read only so N1MM can keep logging to it
+pub struct Database(rusqlite::Connection);
+
+impl Database {
+    pub fn open(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let connection = rusqlite::Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        Ok(Self(connection))
+    }
+
+    /// Every contest in the file, oldest first
+    pub fn contests(&self) -> anyhow::Result<Vec<Contest>> {
+        let mut statement = self.0.prepare(
+            "SELECT ContestNR, ContestName, StartDate, Operator,
+                (SELECT COUNT(*) FROM DXLOG WHERE DXLOG.ContestNR = ContestInstance.ContestNR)
+            FROM ContestInstance ORDER BY ContestNR",
+        )?;
+        let contests = statement
+            .query_map([], |row| {
+                Ok(Contest {
+                    number: row.get(0)?,
+                    name: row.get(1)?,
+                    start: row.get(2)?,
+                    callsign: row.get::<_, Option<String>>(3)?.filter(|c| !c.is_empty()),
+                    contacts: row.get(4)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(contests)
+    }
+
+    pub fn contest(&self, number: i32) -> anyhow::Result<Contest> {
+        self.contests()?
+            .into_iter()
+            .find(|c| c.number == number)
+            .ok_or_else(|| anyhow::anyhow!("No contest {} in the N1MM database", number))
+    }
+
+    /// The contest N1MM made last, which is usually the one being logged
+    pub fn latest_contest(&self) -> anyhow::Result<Contest> {
+        self.contests()?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("No contests in the N1MM database"))
+    }
+
+    /// The contacts of a contest, oldest first
+    pub fn log(&self, contest: &Contest) -> anyhow::Result<Vec<LogEntry>> {
+        let mut statement = self.0.prepare(
+            "SELECT ID, Call, Snt, Rcv, TS, Mode, Band, Freq, QSXFreq,
+                Exchange1, Sect, WPXPrefix, Zone, Operator,
+                IsMultiplier1, IsMultiplier2, IsMultiplier3, IsRunQSO, ClaimedQso, Points,
+                GridSquare, SntNr, RcvNr, Name, Power, QTH, Prec, CK, Comment,
+                Continent, CountryPrefix, RadioNR, StationName, NetBiosName
+            FROM DXLOG WHERE ContestNR = ?1 ORDER BY TS",
+        )?;
+        let entries = statement
+            .query_map([contest.number], |row| {
+                let mode: String = row.get(5)?;
+                let freq_rx = hz_from_khz(row.get(7)?);
+                let qsx: Option<f64> = row.get(8)?;
+                Ok(LogEntry {
+                    id: row.get(0)?,
+                    contest: contest.clone(),
+
+                    recv_callsign: row.get(1)?,
+                    sent_signal_report: signal_report(row.get(2)?, &mode),
+                    recv_signal_report: signal_report(row.get(3)?, &mode),
+                    timestamp: row.get::<_, String>(4).and_then(|ts| {
+                        parse_timestamp(&ts).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                4,
+                                rusqlite::types::Type::Text,
+                                e.into(),
+                            )
+                        })
+                    })?,
+
+                    band: row.get(6)?,
+                    freq_rx,
+                    // `QSXFreq` is only set when working split
+                    freq_tx: qsx.filter(|f| *f > 0.0).map_or(freq_rx, hz_from_khz),
+                    mode,
+
+                    exchange1: non_empty(row.get(9)?),
+                    section: non_empty(row.get(10)?),
+                    prefix_wpx: non_empty(row.get(11)?),
+                    cq_zone: row.get::<_, Option<i16>>(12)?.unwrap_or_default(),
+                    operator: non_empty(row.get(13)?),
+
+                    is_mult_1: row.get::<_, Option<i32>>(14)?.unwrap_or_default() != 0,
+                    is_mult_2: row.get::<_, Option<i32>>(15)?.unwrap_or_default() != 0,
+                    is_mult_3: row.get::<_, Option<i32>>(16)?.unwrap_or_default() != 0,
+
+                    is_run_qso: row.get::<_, Option<i32>>(17)?.unwrap_or_default() != 0,
+                    is_claimed_qso: row.get::<_, Option<i32>>(18)?.unwrap_or(1) != 0,
+                    points: row.get::<_, Option<i32>>(19)?.unwrap_or_default(),
+
+                    grid_square: non_empty(row.get(20)?),
+                    sent_number: row.get(21)?,
+                    recv_number: row.get(22)?,
+                    recv_name: non_empty(row.get(23)?),
+                    recv_power: non_empty(row.get(24)?),
+                    recv_qth: non_empty(row.get(25)?),
+                    prec: non_empty(row.get(26)?),
+                    ck: row.get(27)?,
+                    comment: non_empty(row.get(28)?),
+                    continent: non_empty(row.get(29)?),
+                    country_prefix: non_empty(row.get(30)?),
+                    radio_number: row.get(31)?,
+                    station_name: non_empty(row.get(32)?),
+                    net_bios_name: non_empty(row.get(33)?),
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(entries)
+    }
+}
+
+fn hz_from_khz(khz: f64) -> i64 {
+    (khz * 1000.0).round() as i64
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+/// N1MM leaves the report empty in contests that don't exchange one
+fn signal_report(value: Option<String>, mode: &str) -> RST {
+    value
+        .and_then(|v| RST::try_from(v.as_str()).ok())
+        .unwrap_or_else(|| match mode {
+            "CW" | "RTTY" | "PSK31" | "PSK63" => RST::new(5, 9, Some(9)).unwrap(),
+            _ => RST::new(5, 9, None).unwrap(),
+        })
+}
+
+fn parse_timestamp(ts: &str) -> Result<time::PrimitiveDateTime, time::error::Parse> {
+    time::PrimitiveDateTime::parse(
+        ts,
+        time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+    )
+}
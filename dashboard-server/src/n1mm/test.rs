@@ -0,0 +1,120 @@
+use super::{Contest, Database};
+use crate::rst::RST;
+
+/// The parts of N1MM's schema the importer reads
+const SCHEMA: &str = "
+CREATE TABLE ContestInstance (
+    ContestID INTEGER PRIMARY KEY, ContestName TEXT, StartDate TEXT, Operator TEXT,
+    SentExchange TEXT, ContestNR INTEGER
+);
+CREATE TABLE DXLOG (
+    ContestNR INTEGER, Call TEXT, TS TEXT, Band REAL, Freq REAL, QSXFreq REAL, Mode TEXT,
+    Snt TEXT, SntNr INTEGER, Rcv TEXT, RcvNr INTEGER, Exchange1 TEXT, Sect TEXT,
+    WPXPrefix TEXT, Zone INTEGER, Operator TEXT, IsMultiplier1 INTEGER,
+    IsMultiplier2 INTEGER, IsMultiplier3 INTEGER, IsRunQSO INTEGER, ClaimedQso INTEGER,
+    Points INTEGER, ID TEXT, GridSquare TEXT, Name TEXT, Power TEXT, QTH TEXT, Prec TEXT,
+    CK INTEGER, Comment TEXT, Continent TEXT, CountryPrefix TEXT, RadioNR INTEGER,
+    StationName TEXT, NetBiosName TEXT
+);
+INSERT INTO ContestInstance VALUES (1, 'CQWWCW', '2023-11-25 00:00:00', 'W9YB', '4', 1);
+INSERT INTO ContestInstance VALUES (2, 'FD', '2024-06-22 18:00:00', '', '3A IN', 2);
+INSERT INTO DXLOG VALUES (1, 'DL1ABC', '2023-11-25 00:01:23', 14, 14025.3, 0, 'CW',
+    '599', 1, '579', 0, '14', '', 'DL1', 14, 'K9ET', 1, 0, 0, 1, 1, 3,
+    'a1b2c3d4e5f60718293a4b5c6d7e8f90', 'JO62', '', 'KW', NULL, NULL, 0, '', 'EU', 'DL', 1,
+    'RUN', 'SHACK-PC');
+INSERT INTO DXLOG VALUES (1, 'JA1XYZ', '2023-11-25 00:00:05', 7, 7012, 7015.5, 'CW',
+    '599', 2, '599', 0, '25', NULL, 'JA1', 25, '', 0, 1, 0, 0, 0, 3,
+    '0f1e2d3c4b5a69788796a5b4c3d2e1f0', '', NULL, NULL, NULL, NULL, 0, NULL, 'AS', 'JA', 2,
+    'MULT', 'SHACK-PC');
+INSERT INTO DXLOG VALUES (2, 'K1ABC', '2024-06-22 18:05:00', 144, 144200, 0, 'USB',
+    '', 0, '', 0, '2A', 'CT', 'K1', 5, 'K9ET', 1, 0, 0, 1, 1, 1,
+    '00112233445566778899aabbccddeeff', 'FN31', 'BOB', NULL, 'CT', 'A', 76, 'first',
+    'NA', 'K', 1, '', '');
+";
+
+fn database(name: &str) -> (Database, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!(
+        "dashboard-n1mm-{}-{}.s3db",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute_batch(SCHEMA)
+        .unwrap();
+    (Database::open(&path).unwrap(), path)
+}
+
+#[test]
+fn contests() {
+    let (db, path) = database("contests");
+    let contests = db.contests().unwrap();
+    assert_eq!(
+        contests,
+        vec![
+            Contest {
+                number: 1,
+                name: "CQWWCW".to_owned(),
+                start: Some("2023-11-25 00:00:00".to_owned()),
+                callsign: Some("W9YB".to_owned()),
+                contacts: 2,
+            },
+            Contest {
+                number: 2,
+                name: "FD".to_owned(),
+                start: Some("2024-06-22 18:00:00".to_owned()),
+                callsign: None,
+                contacts: 1,
+            },
+        ]
+    );
+    assert_eq!(db.latest_contest().unwrap().number, 2);
+    assert!(db.contest(3).is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn log() {
+    let (db, path) = database("log");
+    let log = db.log(&db.contest(1).unwrap()).unwrap();
+    assert_eq!(
+        log.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+        vec![
+            "0f1e2d3c4b5a69788796a5b4c3d2e1f0",
+            "a1b2c3d4e5f60718293a4b5c6d7e8f90"
+        ]
+    );
+
+    let split = &log[0];
+    assert_eq!(
+        split.timestamp,
+        time::macros::datetime!(2023-11-25 00:00:05)
+    );
+    assert_eq!((split.freq_rx, split.freq_tx), (7_012_000, 7_015_500));
+    assert_eq!(split.operator, None);
+    assert!(!split.is_claimed_qso);
+    assert!(split.is_mult_2);
+
+    let entry = &log[1];
+    assert_eq!((entry.freq_rx, entry.freq_tx), (14_025_300, 14_025_300));
+    assert_eq!(entry.recv_signal_report, RST::new(5, 7, Some(9)).unwrap());
+    assert_eq!(entry.cq_zone, 14);
+    assert_eq!(entry.contest.callsign.as_deref(), Some("W9YB"));
+    assert_eq!(entry.grid_square.as_deref(), Some("JO62"));
+    assert_eq!((entry.sent_number, entry.recv_number), (Some(1), Some(0)));
+    assert_eq!(entry.recv_name, None);
+    assert_eq!(entry.recv_power.as_deref(), Some("KW"));
+    assert_eq!(entry.country_prefix.as_deref(), Some("DL"));
+    assert_eq!(entry.station_name.as_deref(), Some("RUN"));
+    assert_eq!(entry.net_bios_name.as_deref(), Some("SHACK-PC"));
+
+    // Field Day has no signal reports
+    let fd = db.log(&db.contest(2).unwrap()).unwrap();
+    assert_eq!(fd[0].sent_signal_report, RST::new(5, 9, None).unwrap());
+    assert_eq!(fd[0].section.as_deref(), Some("CT"));
+    assert_eq!(fd[0].recv_qth.as_deref(), Some("CT"));
+    assert_eq!((fd[0].prec.as_deref(), fd[0].ck), (Some("A"), Some(76)));
+    assert_eq!(fd[0].station_name, None);
+    std::fs::remove_file(path).unwrap();
+}
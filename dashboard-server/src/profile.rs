@@ -0,0 +1,47 @@
+/// What HamQTH knows about the operator of a callsign
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    async_graphql::SimpleObject,
+    diesel::Queryable,
+    diesel::Selectable,
+    diesel::Insertable,
+)]
+#[graphql(complex)]
+#[diesel(table_name = crate::schema::callsign_profiles)]
+#[diesel(check_for_backend(crate::database::DbBackend))]
+pub struct Profile {
+    /// See [`crate::location_cache::normalize`]
+    pub callsign: String,
+
+    /// The name in the operator's address
+    pub name: Option<String>,
+    /// What the operator goes by on the air
+    pub nick: Option<String>,
+    pub qth: Option<String>,
+    pub country: Option<String>,
+    pub grid: Option<String>,
+    /// The ADIF entity number
+    pub dxcc: Option<i32>,
+    pub itu_zone: Option<i32>,
+    pub cq_zone: Option<i32>,
+    pub continent: Option<String>,
+    /// `None` when HamQTH doesn't know
+    pub lotw: Option<bool>,
+    pub eqsl: Option<bool>,
+
+    #[graphql(skip)]
+    pub fetched_at: time::PrimitiveDateTime,
+}
+
+#[async_graphql::ComplexObject]
+impl Profile {
+    async fn fetched_at(&self) -> String {
+        self.fetched_at
+            .format(time::macros::format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second]Z"
+            ))
+            .unwrap()
+    }
+}
@@ -0,0 +1,101 @@
+use diesel::{deserialize::FromSql, serialize::ToSql};
+
+use crate::contact_data::ContactData;
+
+/// A recorded change to a contact, deleted contacts can be restored from one
+#[derive(Debug, Clone, async_graphql::SimpleObject, diesel::Queryable, diesel::Selectable)]
+#[graphql(complex)]
+#[diesel(table_name = crate::schema::contact_revisions)]
+#[diesel(check_for_backend(crate::database::DbBackend))]
+pub struct Revision {
+    pub id: i32,
+    pub contact_id: String,
+    pub action: Action,
+
+    #[graphql(skip)]
+    pub recorded_at: time::PrimitiveDateTime,
+
+    /// The N1MM station that sent the change
+    pub station_name: Option<String>,
+    /// The XML packet of the change
+    pub packet: Option<String>,
+
+    #[graphql(skip)]
+    pub snapshot: String,
+}
+
+#[async_graphql::ComplexObject]
+impl Revision {
+    async fn recorded_at(&self) -> String {
+        self.recorded_at
+            .format(time::macros::format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second]Z"
+            ))
+            .unwrap()
+    }
+
+    /// The contact as it was before a delete and after any other change
+    async fn contact(&self) -> async_graphql::Result<ContactData> {
+        Ok(self.parse_snapshot()?)
+    }
+}
+
+impl Revision {
+    pub fn parse_snapshot(&self) -> serde_json::Result<ContactData> {
+        serde_json::from_str(&self.snapshot)
+    }
+}
+
+/// Where a change to a contact came from, nothing for imports and restores
+#[derive(Debug, Clone, Default)]
+pub struct Origin {
+    pub station_name: Option<String>,
+    pub packet: Option<String>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, diesel::AsExpression, diesel::FromSqlRow, async_graphql::Enum,
+)]
+#[diesel(sql_type = diesel::sql_types::VarChar)]
+pub enum Action {
+    Insert,
+    Replace,
+    /// The contact was marked deleted
+    Delete,
+    /// The contact was set back to an earlier revision
+    Restore,
+}
+
+impl<DB: diesel::backend::Backend> FromSql<diesel::sql_types::VarChar, DB> for Action
+where
+    String: FromSql<diesel::sql_types::VarChar, DB>,
+{
+    fn from_sql(
+        bytes: <DB as diesel::backend::Backend>::RawValue<'_>,
+    ) -> diesel::deserialize::Result<Self> {
+        match String::from_sql(bytes)?.as_str() {
+            "Insert" => Ok(Action::Insert),
+            "Replace" => Ok(Action::Replace),
+            "Delete" => Ok(Action::Delete),
+            "Restore" => Ok(Action::Restore),
+            s => Err(format!("Unknown revision action `{}`", s).into()),
+        }
+    }
+}
+
+impl<DB: diesel::backend::Backend> ToSql<diesel::sql_types::VarChar, DB> for Action
+where
+    str: ToSql<diesel::sql_types::VarChar, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Action::Insert => "Insert".to_sql(out),
+            Action::Replace => "Replace".to_sql(out),
+            Action::Delete => "Delete".to_sql(out),
+            Action::Restore => "Restore".to_sql(out),
+        }
+    }
+}
@@ -131,6 +131,15 @@ impl<'de> serde::Deserialize<'de> for RST {
     }
 }
 
+impl serde::Serialize for RST {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 #[async_graphql::Scalar]
 impl async_graphql::ScalarType for RST {
     fn parse(value: async_graphql::Value) -> async_graphql::InputValueResult<Self> {
@@ -166,6 +175,18 @@ impl diesel::serialize::ToSql<diesel::sql_types::Text, diesel::sqlite::Sqlite> f
     }
 }
 
+#[cfg(feature = "postgres")]
+impl diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg> for RST {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>,
+    ) -> diesel::serialize::Result {
+        use std::io::Write;
+        write!(out, "{}", self)?;
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 #[rustc_layout_scalar_valid_range_start(1)]
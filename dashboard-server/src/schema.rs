@@ -1,5 +1,46 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    callsign_locations (callsign) {
+        callsign -> Text,
+        location_source -> Text,
+        latitude -> Nullable<Float>,
+        longitude -> Nullable<Float>,
+        fetched_at -> Timestamp,
+        ttl -> Integer,
+    }
+}
+
+diesel::table! {
+    callsign_profiles (callsign) {
+        callsign -> Text,
+        name -> Nullable<Text>,
+        nick -> Nullable<Text>,
+        qth -> Nullable<Text>,
+        country -> Nullable<Text>,
+        grid -> Nullable<Text>,
+        dxcc -> Nullable<Integer>,
+        itu_zone -> Nullable<Integer>,
+        cq_zone -> Nullable<Integer>,
+        continent -> Nullable<Text>,
+        lotw -> Nullable<Bool>,
+        eqsl -> Nullable<Bool>,
+        fetched_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    contact_revisions (id) {
+        id -> Integer,
+        contact_id -> Text,
+        action -> Text,
+        recorded_at -> Timestamp,
+        station_name -> Nullable<Text>,
+        packet -> Nullable<Text>,
+        snapshot -> Text,
+    }
+}
+
 diesel::table! {
     contacts (id) {
         id -> Nullable<Text>,
@@ -26,5 +67,55 @@ diesel::table! {
         location_source -> Text,
         latitude -> Nullable<Float>,
         longitude -> Nullable<Float>,
+        band -> Nullable<Text>,
+        log_name -> Nullable<Text>,
+        contest_id -> Nullable<Integer>,
+        grid_square -> Nullable<Text>,
+        sent_number -> Nullable<Integer>,
+        recv_number -> Nullable<Integer>,
+        recv_name -> Nullable<Text>,
+        recv_power -> Nullable<Text>,
+        recv_qth -> Nullable<Text>,
+        prec -> Nullable<Text>,
+        ck -> Nullable<Integer>,
+        comment -> Nullable<Text>,
+        continent -> Nullable<Text>,
+        country_prefix -> Nullable<Text>,
+        radio_number -> Nullable<Integer>,
+        station_name -> Nullable<Text>,
+        net_bios_name -> Nullable<Text>,
+        deleted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    contests (id) {
+        id -> Integer,
+        contest_number -> Integer,
+        contest_name -> Text,
+        db_name -> Nullable<Text>,
+        first_seen -> Timestamp,
+        last_seen -> Timestamp,
     }
 }
+
+diesel::table! {
+    location_lookups (contact_id) {
+        contact_id -> Text,
+        attempts -> Integer,
+        queued_at -> Timestamp,
+        next_run -> Timestamp,
+        last_error -> Nullable<Text>,
+    }
+}
+
+diesel::joinable!(contacts -> contests (contest_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    callsign_locations,
+    callsign_profiles,
+    contact_revisions,
+    contacts,
+    contests,
+    location_lookups,
+);
@@ -1,16 +1,15 @@
 use std::sync::{Arc, Mutex};
 
-use crate::{contact_data, database, hamqth, xml};
+use crate::{contact_data, database, location, revision, xml};
 
 pub async fn udp_receiver(
     db: database::Database,
-    hamqth_session: Option<hamqth::Session>,
+    providers: location::Chain,
 ) -> anyhow::Result<()> {
     let db = Arc::new(db);
-    let hamqth_session = hamqth_session.map(|s| Arc::new(s));
     let socket = Arc::new(Mutex::new(std::net::UdpSocket::bind("0.0.0.0:12063")?));
     loop {
-        match receive_udp(db.clone(), hamqth_session.clone(), socket.clone()).await {
+        match receive_udp(db.clone(), providers.clone(), socket.clone()).await {
             Ok(_) => log::trace!("Successfully received UDP packet"),
             Err(e) => log::warn!("Error receiving UDP packet: {}", e),
         }
@@ -19,7 +18,7 @@ pub async fn udp_receiver(
 
 async fn receive_udp(
     db: Arc<database::Database>,
-    hamqth_session: Option<Arc<hamqth::Session>>,
+    providers: location::Chain,
     socket: Arc<Mutex<std::net::UdpSocket>>,
 ) -> anyhow::Result<()> {
     const N: usize = 8192;
@@ -37,7 +36,7 @@ async fn receive_udp(
     };
 
     tokio::spawn(async move {
-        match process_udp(&db, hamqth_session.as_deref(), data).await {
+        match process_udp(&db, &providers, data).await {
             Ok(_) => log::trace!("Successfully processed UDP packet"),
             Err(e) => log::warn!("Error processing UDP packet: {}", e),
         }
@@ -48,7 +47,7 @@ async fn receive_udp(
 
 async fn process_udp(
     db: &database::Database,
-    hamqth_session: Option<&hamqth::Session>,
+    providers: &location::Chain,
     data: Vec<u8>,
 ) -> anyhow::Result<()> {
     let xml = std::str::from_utf8(&data)?;
@@ -58,14 +57,45 @@ async fn process_udp(
     match data {
         xml::UdpData::ContactInfo(info) | xml::UdpData::ContactReplace(info) => {
             log::info!("Got new contact {:?}", info);
-            let cd = contact_data::ContactData::from(info);
-            if let Some(session) = hamqth_session {
-                db.update_and_fetch_location(session, &cd, true).await?;
-            }
+            let origin = revision::Origin {
+                station_name: Some(info.station_name.to_owned()),
+                packet: Some(xml.to_owned()),
+            };
+            let contest = db
+                .seen_contest(
+                    info.contest_number,
+                    info.contest_name.unwrap_or_default(),
+                    None,
+                )
+                .await?;
+            let mut cd = contact_data::ContactData::from(info);
+            cd.contest_id = Some(contest);
+            db.update_and_fetch_location(providers, &cd, &origin, true)
+                .await?;
         }
         xml::UdpData::ContactDelete(data) => {
             log::info!("Got contact delete {:?}", data);
-            db.delete_by_id(data.id).await?;
+            let origin = revision::Origin {
+                station_name: Some(data.station_name.to_owned()),
+                packet: Some(xml.to_owned()),
+            };
+            db.delete_by_id(data.id, &origin).await?;
+        }
+        xml::UdpData::AppInfo {
+            db_name,
+            contest_number,
+            contest_name,
+            ..
+        } => {
+            db.seen_contest(contest_number, contest_name, Some(db_name))
+                .await?;
+            // what to set N1MM_DATABASE and N1MM_CONTEST to for a backfill
+            log::info!(
+                "N1MM is logging {} (contest {}) to {}",
+                contest_name,
+                contest_number,
+                db_name
+            );
         }
         other => log::info!("Other UDP data: {:?}", other),
     }
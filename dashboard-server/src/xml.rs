@@ -70,14 +70,14 @@ pub struct ContactInfo<'s> {
     #[serde(rename = "snt")]
     pub sent_signal_report: rst::RST,
     #[serde(rename = "sntnr")]
-    sent_number: u32,
+    pub sent_number: u32,
     #[serde(rename = "rcv")]
     pub recv_signal_report: rst::RST,
     #[serde(rename = "rcvnr")]
-    recv_number: u32,
+    pub recv_number: u32,
 
     #[serde(rename = "gridsquare", deserialize_with = "helpers::empty_str_as_none")]
-    grid_square: Option<&'s str>,
+    pub grid_square: Option<&'s str>,
 
     #[serde(deserialize_with = "helpers::empty_str_as_none")]
     pub exchange1: Option<&'s str>,
@@ -86,26 +86,26 @@ pub struct ContactInfo<'s> {
     pub section: Option<&'s str>,
 
     #[serde(deserialize_with = "helpers::empty_str_as_none")]
-    comment: Option<&'s str>,
+    pub comment: Option<&'s str>,
 
     #[serde(rename = "qth", deserialize_with = "helpers::empty_str_as_none")]
-    location: Option<&'s str>,
+    pub location: Option<&'s str>,
 
     #[serde(deserialize_with = "helpers::empty_str_as_none")]
-    name: Option<&'s str>,
+    pub name: Option<&'s str>,
 
     #[serde(deserialize_with = "helpers::empty_str_as_none")]
-    power: Option<&'s str>,
+    pub power: Option<&'s str>,
 
     #[serde(deserialize_with = "helpers::empty_str_as_none")]
     misctext: Option<&'s str>,
 
     #[serde(deserialize_with = "helpers::empty_str_as_none")]
-    prec: Option<&'s str>,
+    pub prec: Option<&'s str>,
 
     #[serde(rename = "zone")]
     pub cq_zone: u8,
-    ck: u32,
+    pub ck: u32,
 
     #[serde(rename = "ismultiplier1", deserialize_with = "bool_from_int")]
     pub is_mult_1: bool,
@@ -136,7 +136,7 @@ pub struct ContactInfo<'s> {
         rename = "NetBiosName",
         deserialize_with = "helpers::empty_str_as_none"
     )]
-    net_bios_name: Option<&'s str>,
+    pub net_bios_name: Option<&'s str>,
 
     #[serde(rename = "IsRunQSO", deserialize_with = "bool_from_int")]
     pub is_run_qso: bool,
@@ -146,7 +146,7 @@ pub struct ContactInfo<'s> {
     pub points: i32,
 
     #[serde(rename = "StationName")]
-    station_name: &'s str,
+    pub station_name: &'s str,
 
     #[serde(rename = "ID")]
     pub id: &'s str,